@@ -1,9 +1,124 @@
+use std::path::PathBuf;
+use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
+
 fn main() {
     linker_be_nice();
+    version_info();
     // make sure linkall.x is the last linker script (otherwise might cause problems with flip-link)
     println!("cargo:rustc-link-arg=-Tlinkall.x");
 }
 
+/// Provides the build metadata reported by `hakkaa::version()` as environment variables.
+fn version_info() {
+    println!("cargo:rerun-if-changed=build.rs");
+
+    let git_hash = git(&["rev-parse", "--short", "HEAD"]).unwrap_or_else(|| "unknown".to_string());
+    println!("cargo:rustc-env=HAKKAA_GIT_HASH={git_hash}");
+    // Pick up new commits and checkouts. HEAD only changes when switching branches, committing
+    // moves the branch it refers to.
+    for path in ["HEAD", "packed-refs"]
+        .into_iter()
+        .map(str::to_string)
+        .chain(git(&["symbolic-ref", "-q", "HEAD"]))
+        .filter_map(|path| git(&["rev-parse", "--git-path", &path]))
+    {
+        if std::path::Path::new(&path).exists() {
+            println!("cargo:rerun-if-changed={path}");
+        }
+    }
+
+    // Honor reproducible builds if requested.
+    println!("cargo:rerun-if-env-changed=SOURCE_DATE_EPOCH");
+    let timestamp = std::env::var("SOURCE_DATE_EPOCH")
+        .ok()
+        .and_then(|epoch| epoch.parse::<u64>().ok())
+        .unwrap_or_else(|| {
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|duration| duration.as_secs())
+                .unwrap_or_default()
+        });
+    println!("cargo:rustc-env=HAKKAA_BUILD_TIMESTAMP={timestamp}");
+
+    let mut features: Vec<String> = std::env::vars()
+        .filter_map(|(key, _)| key.strip_prefix("CARGO_FEATURE_").map(str::to_string))
+        .map(|feature| feature.to_lowercase().replace('_', "-"))
+        // Not a feature of its own but the set of features enabled by default.
+        .filter(|feature| feature != "default")
+        .collect();
+    features.sort();
+    println!("cargo:rustc-env=HAKKAA_FEATURES={}", features.join(","));
+
+    println!("cargo:rustc-env=HAKKAA_DEPENDENCIES={}", dependencies());
+}
+
+/// The crates reported with their versions by `hakkaa::version()`.
+const REPORTED_DEPENDENCIES: [&str; 6] = [
+    "embassy-executor",
+    "embassy-sync",
+    "embassy-time",
+    "esp-hal",
+    "esp-println",
+    "esp-rtos",
+];
+
+/// Looks up the versions of the reported dependencies from `Cargo.lock`. Returns them as
+/// comma-separated list of `name version` entries or an empty string if there is no lock file.
+fn dependencies() -> String {
+    let lock = lock_file()
+        .inspect(|path| println!("cargo:rerun-if-changed={}", path.display()))
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .unwrap_or_default();
+    let mut dependencies = Vec::new();
+    let mut name = None;
+
+    for line in lock.lines() {
+        if let Some(value) = line.strip_prefix("name = ") {
+            name = Some(value.trim_matches('"').to_string());
+        } else if let Some(value) = line.strip_prefix("version = ") {
+            if let Some(name) = name
+                .take()
+                .filter(|name| REPORTED_DEPENDENCIES.contains(&name.as_str()))
+            {
+                dependencies.push(format!("{name} {}", value.trim_matches('"')));
+            }
+        }
+    }
+
+    dependencies.sort();
+    dependencies.join(",")
+}
+
+/// Finds the `Cargo.lock` of the build. This is the lock file of the workspace building this
+/// crate, which is not necessarily this crate's own one when it is used as a dependency.
+///
+/// Looks in the directories above `OUT_DIR` first, as the target directory usually is located
+/// in the workspace. Falls back to the directories above the manifest for target directories
+/// elsewhere.
+fn lock_file() -> Option<PathBuf> {
+    ["OUT_DIR", "CARGO_MANIFEST_DIR"]
+        .into_iter()
+        .filter_map(|key| std::env::var_os(key).map(PathBuf::from))
+        .find_map(|dir| {
+            dir.ancestors()
+                .map(|ancestor| ancestor.join("Cargo.lock"))
+                .find(|path| path.is_file())
+        })
+}
+
+/// Runs git with `args` and returns its trimmed output. Returns `None` if git is not available or
+/// failed, for example when building from a source archive.
+fn git(args: &[&str]) -> Option<String> {
+    Command::new("git")
+        .args(args)
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .and_then(|output| String::from_utf8(output.stdout).ok())
+        .map(|output| output.trim().to_string())
+}
+
 fn linker_be_nice() {
     let args: Vec<String> = std::env::args().collect();
     if args.len() > 1 {
@@ -0,0 +1,58 @@
+#![no_std]
+#![no_main]
+#![deny(
+    clippy::mem_forget,
+    reason = "mem::forget is generally not safe to do with esp_hal types, especially those \
+    holding buffers for the duration of a data transfer."
+)]
+
+use embassy_executor::Spawner;
+use embassy_futures::select::{select, Either};
+use embedded_can::Frame;
+use esp_backtrace as _;
+use esp_hal::twai::{BaudRate, StandardId};
+use hakkaa::board::Board;
+use hakkaa::bus::can::{self, Can};
+use hakkaa::led::Storeys;
+
+// This creates a default app-descriptor required by the esp-idf bootloader.
+// For more information see: <https://docs.espressif.com/projects/esp-idf/en/stable/esp32/api-reference/system/app_image_format.html#application-description>
+esp_bootloader_esp_idf::esp_app_desc!();
+
+/// The identifier of the frames carrying storey patterns. Every board sends and receives them.
+const PATTERN_ID: u16 = 0x100;
+
+#[esp_rtos::main]
+async fn main(_spawner: Spawner) -> ! {
+    let mut board = Board::init();
+    let p = board.peripherals;
+    let id = StandardId::new(PATTERN_ID).unwrap();
+
+    let mut config = can::configure(p.twai0, p.gpio2, p.gpio9, BaudRate::B125K);
+    config.set_filter(can::accept_only(id));
+    let mut can = Can::new(config);
+    let mut storeys = Storeys::new(board.storey_leds);
+    let mut pattern = 0x01u8;
+
+    log::info!("Shake the board to send the next pattern to all boards on the bus.");
+
+    loop {
+        match select(can.receive(), board.u2.wait_for_shake()).await {
+            Either::First(Ok(frame)) => {
+                if let Some(received) = frame.data().first() {
+                    log::info!("received pattern {:08b}", received);
+                    pattern = *received;
+                }
+            }
+            Either::First(Err(e)) => log::warn!("receive failed: {:?}", e),
+            Either::Second(_) => {
+                pattern = pattern.rotate_left(1);
+                if let Err(e) = can.send(id, &[pattern]).await {
+                    log::warn!("send failed: {:?}", e);
+                }
+            }
+        }
+
+        storeys.set_pattern(pattern);
+    }
+}
@@ -0,0 +1,71 @@
+#![no_std]
+#![no_main]
+#![deny(
+    clippy::mem_forget,
+    reason = "mem::forget is generally not safe to do with esp_hal types, especially those \
+    holding buffers for the duration of a data transfer."
+)]
+
+use embassy_executor::Spawner;
+use esp_backtrace as _;
+use hakkaa::board::Board;
+use hakkaa::climate::Sht3x;
+use hakkaa::i2c;
+use hakkaa::led::{Period, Storeys, STOREY_LEDS};
+use hakkaa::sensor::{self, ChipTemperature, Poller, Value};
+use hakkaa::task;
+
+// This creates a default app-descriptor required by the esp-idf bootloader.
+// For more information see: <https://docs.espressif.com/projects/esp-idf/en/stable/esp32/api-reference/system/app_image_format.html#application-description>
+esp_bootloader_esp_idf::esp_app_desc!();
+
+/// Returns a bar graph pattern for the relative `humidity` in percent.
+fn bar(humidity: f32) -> u8 {
+    let lit = (humidity / 100.0 * STOREY_LEDS as f32).clamp(0.0, STOREY_LEDS as f32);
+    0xffu8.checked_shl(lit as u32).map_or(0xff, |mask| !mask)
+}
+
+/// Logs all readings published and shows the humidity as bar graph on the storeys.
+#[embassy_executor::task]
+async fn display_task(mut readings: sensor::Subscriber, mut storeys: Storeys<'static>) {
+    loop {
+        let reading = readings.next_message_pure().await;
+        log::info!("{}: {:?}", reading.sensor, reading.value);
+
+        if let Value::Humidity(humidity) = reading.value {
+            storeys.set_pattern(bar(humidity));
+        }
+    }
+}
+
+#[esp_rtos::main]
+async fn main(spawner: Spawner) -> ! {
+    let board = Board::init();
+    let p = board.peripherals;
+
+    // Connect a SHT3x breakout board with SDA to GPIO2 and SCL to GPIO9.
+    let bus = i2c::open(p.i2c0, p.gpio2, p.gpio9).unwrap();
+    let mut sht = Sht3x::new(bus);
+    let mut chip = ChipTemperature::new(p.tsens).unwrap();
+
+    if let Some(readings) = sensor::subscribe() {
+        let storeys = Storeys::new(board.storey_leds);
+        task::spawn_or_report(&spawner, "display", display_task(readings, storeys));
+    }
+
+    // Sample the humidity every five seconds and compare the room temperature with the one of the
+    // chip every minute.
+    let mut poller = Poller::<3>::new();
+    let humidity_slot = poller.add(Period::from_secs(5)).unwrap();
+    let sht_slot = poller.add(Period::from_secs(60)).unwrap();
+    let chip_slot = poller.add(Period::from_secs(60)).unwrap();
+
+    loop {
+        match poller.next().await {
+            slot if slot == humidity_slot => sensor::poll(&mut sht.humidity()).await,
+            slot if slot == sht_slot => sensor::poll(&mut sht).await,
+            slot if slot == chip_slot => sensor::poll(&mut chip).await,
+            _ => None,
+        };
+    }
+}
@@ -0,0 +1,54 @@
+#![no_std]
+#![no_main]
+#![deny(
+    clippy::mem_forget,
+    reason = "mem::forget is generally not safe to do with esp_hal types, especially those \
+    holding buffers for the duration of a data transfer."
+)]
+
+use embassy_executor::Spawner;
+use embassy_time::{Duration, Ticker};
+use esp_backtrace as _;
+use hakkaa::board::Board;
+use hakkaa::i2c;
+use hakkaa::led::Storeys;
+use hakkaa::rtc::{DateTime, Ds3231};
+
+// This creates a default app-descriptor required by the esp-idf bootloader.
+// For more information see: <https://docs.espressif.com/projects/esp-idf/en/stable/esp32/api-reference/system/app_image_format.html#application-description>
+esp_bootloader_esp_idf::esp_app_desc!();
+
+#[esp_rtos::main]
+async fn main(_spawner: Spawner) -> ! {
+    let board = Board::init();
+    let p = board.peripherals;
+
+    // Connect a DS3231 module with SDA to GPIO2 and SCL to GPIO9.
+    let bus = i2c::open(p.i2c0, p.gpio2, p.gpio9).unwrap();
+    let mut rtc = Ds3231::new(bus);
+    let mut storeys = Storeys::new(board.storey_leds);
+
+    // A clock which lost its time starts over from when the firmware got built.
+    if let Ok(None) = rtc.now().await {
+        if let Some(build) = DateTime::from_unix(hakkaa::version().build_timestamp) {
+            log::info!("setting the clock to the build time {}", build);
+            if let Err(e) = rtc.set(build).await {
+                log::warn!("setting the clock failed: {:?}", e);
+            }
+        }
+    }
+
+    // Log the time every second and show the minute in binary on the storeys.
+    let mut ticker = Ticker::every(Duration::from_secs(1));
+    loop {
+        match rtc.now().await {
+            Ok(Some(now)) => {
+                log::info!("{}", now);
+                storeys.set_pattern(now.minute);
+            }
+            Ok(None) => log::warn!("the clock lost its time"),
+            Err(e) => log::warn!("reading the clock failed: {:?}", e),
+        }
+        ticker.next().await;
+    }
+}
@@ -0,0 +1,60 @@
+#![no_std]
+#![no_main]
+#![deny(
+    clippy::mem_forget,
+    reason = "mem::forget is generally not safe to do with esp_hal types, especially those \
+    holding buffers for the duration of a data transfer."
+)]
+
+use embassy_executor::Spawner;
+use esp_backtrace as _;
+use esp_hal::gpio::{Input, InputConfig, Level, Output, OutputConfig};
+use hakkaa::board::Board;
+use hakkaa::led::{Storeys, STOREY_LEDS};
+use hakkaa::ultrasonic::{DistanceSensor, Event};
+
+// This creates a default app-descriptor required by the esp-idf bootloader.
+// For more information see: <https://docs.espressif.com/projects/esp-idf/en/stable/esp32/api-reference/system/app_image_format.html#application-description>
+esp_bootloader_esp_idf::esp_app_desc!();
+
+/// The distance covered by a single storey LED in millimeters.
+const MM_PER_STOREY: u32 = 50;
+
+/// Returns a bar graph pattern with the lower `n` storey LEDs lit.
+fn bar(n: u32) -> u8 {
+    match n {
+        0 => 0,
+        n if n >= STOREY_LEDS as u32 => 0xff,
+        n => (1 << n) - 1,
+    }
+}
+
+#[esp_rtos::main]
+async fn main(_spawner: Spawner) -> ! {
+    let board = Board::init();
+
+    // Connect the trigger of an HC-SR04 to GPIO9 and its echo through a voltage divider to GPIO2.
+    // The echo is idle low and would prevent booting the firmware when connected to the strapping
+    // pin GPIO9.
+    let trigger = Output::new(board.peripherals.gpio9, Level::Low, OutputConfig::default());
+    let echo = Input::new(board.peripherals.gpio2, InputConfig::default());
+    let mut sensor = DistanceSensor::new(trigger, echo).with_threshold(10);
+    let mut storeys = Storeys::new(board.storey_leds);
+
+    log::info!("Move your hand in front of the distance sensor.");
+
+    // Show the distance as a bar graph growing with the distance. Update it when the distance
+    // changed by more than a centimeter for keeping it from flickering.
+    loop {
+        match sensor.next().await {
+            Event::Moved(distance) => {
+                log::info!("distance: {} mm", distance);
+                storeys.set_pattern(bar(distance / MM_PER_STOREY));
+            }
+            Event::Lost => {
+                log::info!("out of range");
+                storeys.all_off();
+            }
+        }
+    }
+}
@@ -0,0 +1,49 @@
+#![no_std]
+#![no_main]
+#![deny(
+    clippy::mem_forget,
+    reason = "mem::forget is generally not safe to do with esp_hal types, especially those \
+    holding buffers for the duration of a data transfer."
+)]
+
+use embassy_executor::Spawner;
+use esp_backtrace as _;
+use hakkaa::board::Board;
+use hakkaa::dmx::DmxOutput;
+use hakkaa::led::{Period, Storeys, Ticker};
+
+// This creates a default app-descriptor required by the esp-idf bootloader.
+// For more information see: <https://docs.espressif.com/projects/esp-idf/en/stable/esp32/api-reference/system/app_image_format.html#application-description>
+esp_bootloader_esp_idf::esp_app_desc!();
+
+/// The first DMX channel (counting from one) receiving the storey pattern.
+const FIRST_CHANNEL: usize = 1;
+
+#[esp_rtos::main]
+async fn main(_spawner: Spawner) -> ! {
+    let board = Board::init();
+
+    // Connect the input of an RS-485 transceiver to GPIO9 and its outputs to the DMX line.
+    let mut dmx = DmxOutput::new(board.peripherals.uart1, board.peripherals.gpio9).unwrap();
+    let mut storeys = Storeys::new(board.storey_leds);
+
+    log::info!(
+        "Sending the storey pattern to DMX channels {} to {}.",
+        FIRST_CHANNEL,
+        FIRST_CHANNEL + 7
+    );
+
+    // Run a light up the building and mirror every frame to the fixtures.
+    let mut ticker = Ticker::every(Period::from_millis(250));
+    let mut pattern = 0x01u8;
+    loop {
+        storeys.set_pattern(pattern);
+        dmx.set_storeys(FIRST_CHANNEL, pattern);
+        if let Err(e) = dmx.send().await {
+            log::warn!("sending DMX packet failed: {:?}", e);
+        }
+
+        pattern = pattern.rotate_left(1);
+        ticker.next().await;
+    }
+}
@@ -15,10 +15,10 @@ use esp_backtrace as _;
 
 use esp_hal::gpio::Input;
 use hakkaa::board::Board;
-use hakkaa::led::Storeys;
+use hakkaa::led::{Period, Storeys};
+use hakkaa::shake::ShakeSensor;
 use hakkaa::switch::LowActiveSwitch;
-
-extern crate alloc;
+use hakkaa::task;
 
 type ButtonSignal = Signal<CriticalSectionRawMutex, ()>;
 
@@ -54,7 +54,7 @@ async fn wait_for_button_n_times<'a>(button: &mut Input<'a>, n: usize) {
 }
 
 /// Task waiting for three times an input on `button` and signalling this event through `signal`.
-#[embassy_executor::task(pool_size = 2)]
+#[embassy_executor::task]
 async fn button_task(mut button: Input<'static>, signal: &'static ButtonSignal) {
     loop {
         wait_for_button_n_times(&mut button, 3).await;
@@ -62,6 +62,15 @@ async fn button_task(mut button: Input<'static>, signal: &'static ButtonSignal)
     }
 }
 
+/// Task waiting for shaking `sensor` three times and signalling this event through `signal`.
+#[embassy_executor::task]
+async fn shake_task(mut sensor: ShakeSensor<'static>, signal: &'static ButtonSignal) {
+    loop {
+        sensor.wait_for_n_shakes::<3>(Duration::from_secs(5)).await;
+        signal.signal(());
+    }
+}
+
 /// Task performing the board EOL test by orchestrating LED patterns and checking button inputs.
 #[embassy_executor::task]
 async fn eol_task(
@@ -70,7 +79,7 @@ async fn eol_task(
     second_button: &'static ButtonSignal,
     mut finished_led: LowActiveSwitch<'static>,
 ) {
-    let step = Duration::from_millis(500);
+    let step = Period::from_millis(500);
 
     // Cycle LEDs while waiting for button presses. This should be the most distinguishable action
     // giving the user all the time need for checking the storey LEDs.
@@ -111,15 +120,14 @@ async fn main(spawner: Spawner) -> ! {
     let storeys = Storeys::new(board.storey_leds);
 
     log::info!("Starting end-of-line (EOL) test.");
-    // Spawn a debouncing and counting task for each "button". Each triplet of "presses" will
-    // generate as signal which is later checked by the EOL task.
-    spawner.spawn(button_task(board.sw1, &SW1_SIGNAL)).unwrap();
-    spawner.spawn(button_task(board.u2, &U2_SIGNAL)).unwrap();
+    // Spawn a debouncing and counting task for the button and the shake sensor. Each triplet of
+    // presses or shakes will generate as signal which is later checked by the EOL task.
+    task::spawn_or_report(&spawner, "button", button_task(board.sw1, &SW1_SIGNAL));
+    task::spawn_or_report(&spawner, "shake", shake_task(board.u2, &U2_SIGNAL));
     // Finally spawn the EOL task showing different storey LED patterns for user inspection of LEDs
     // and as a prompt for pressing SW1 or shaking the board for checking the shake sensor U2.
-    spawner
-        .spawn(eol_task(storeys, &SW1_SIGNAL, &U2_SIGNAL, board.esp_led))
-        .unwrap();
+    let eol = eol_task(storeys, &SW1_SIGNAL, &U2_SIGNAL, board.esp_led);
+    task::spawn_or_report(&spawner, "eol", eol);
 
     // Keep the main task running forever.
     loop {
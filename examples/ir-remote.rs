@@ -0,0 +1,57 @@
+#![no_std]
+#![no_main]
+#![deny(
+    clippy::mem_forget,
+    reason = "mem::forget is generally not safe to do with esp_hal types, especially those \
+    holding buffers for the duration of a data transfer."
+)]
+
+use embassy_executor::Spawner;
+use embassy_futures::select::{select, Either};
+use esp_backtrace as _;
+use hakkaa::board::Board;
+use hakkaa::ir::{self, Event, IrReceiver, IrTransmitter};
+use hakkaa::led::Storeys;
+
+// This creates a default app-descriptor required by the esp-idf bootloader.
+// For more information see: <https://docs.espressif.com/projects/esp-idf/en/stable/esp32/api-reference/system/app_image_format.html#application-description>
+esp_bootloader_esp_idf::esp_app_desc!();
+
+#[esp_rtos::main]
+async fn main(_spawner: Spawner) -> ! {
+    let mut board = Board::init();
+    let p = board.peripherals;
+
+    // Connect the output of an IR receiver module to GPIO9 and an IR LED through a transistor to
+    // GPIO2.
+    let rmt = ir::rmt(p.rmt).unwrap();
+    let mut receiver = IrReceiver::new(rmt.channel2, p.gpio9).unwrap();
+    let mut transmitter = IrTransmitter::new(rmt.channel0, p.gpio2).unwrap();
+    let mut storeys = Storeys::new(board.storey_leds);
+
+    log::info!("Press a button on a remote control and shake the board for sending it again.");
+
+    // Show the command of the last button pressed and replay it on a shake.
+    let mut last = None;
+    loop {
+        match select(receiver.receive(), board.u2.wait_for_shake()).await {
+            Either::First(Event::Command(command)) => {
+                log::info!(
+                    "address 0x{:04x}, command 0x{:02x}",
+                    command.address,
+                    command.command
+                );
+                storeys.set_pattern(command.command);
+                last = Some(command);
+            }
+            Either::First(Event::Repeat) => log::debug!("repeat"),
+            Either::Second(_) => {
+                if let Some(command) = last {
+                    if let Err(e) = transmitter.send(command).await {
+                        log::warn!("sending failed: {:?}", e);
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,49 @@
+#![no_std]
+#![no_main]
+#![deny(
+    clippy::mem_forget,
+    reason = "mem::forget is generally not safe to do with esp_hal types, especially those \
+    holding buffers for the duration of a data transfer."
+)]
+
+use embassy_executor::Spawner;
+use embassy_time::{Duration, Timer};
+use esp_backtrace as _;
+use esp_hal::analog::adc::{Adc, AdcConfig, Attenuation};
+use hakkaa::board::Board;
+use hakkaa::knob::{Knob, MAX_POSITION};
+use hakkaa::led::{Storeys, STOREY_LEDS};
+
+// This creates a default app-descriptor required by the esp-idf bootloader.
+// For more information see: <https://docs.espressif.com/projects/esp-idf/en/stable/esp32/api-reference/system/app_image_format.html#application-description>
+esp_bootloader_esp_idf::esp_app_desc!();
+
+/// The full scale of the 12 bit ADC readings.
+const FULL_SCALE: u16 = 4095;
+
+#[esp_rtos::main]
+async fn main(_spawner: Spawner) -> ! {
+    let board = Board::init();
+
+    // Connect the wiper of a potentiometer to GPIO2 and its ends to GND and 3V3.
+    let mut config = AdcConfig::new();
+    let mut pin = config.enable_pin(board.peripherals.gpio2, Attenuation::_11dB);
+    let mut adc = Adc::new(board.peripherals.adc1, config).into_async();
+
+    let mut knob = Knob::new(FULL_SCALE);
+    let mut storeys = Storeys::new(board.storey_leds);
+
+    log::info!("Turn the potentiometer for lighting up the storeys.");
+
+    loop {
+        let reading = adc.read_oneshot(&mut pin).await;
+        if let Some(position) = knob.update(reading) {
+            log::info!("knob: {}", position);
+
+            let lit = u32::from(position) * STOREY_LEDS as u32 / u32::from(MAX_POSITION);
+            storeys.set_pattern(0xffu8.checked_shl(lit).map_or(0xff, |mask| !mask));
+        }
+
+        Timer::after(Duration::from_millis(20)).await;
+    }
+}
@@ -0,0 +1,37 @@
+#![no_std]
+#![no_main]
+#![deny(
+    clippy::mem_forget,
+    reason = "mem::forget is generally not safe to do with esp_hal types, especially those \
+    holding buffers for the duration of a data transfer."
+)]
+
+use embassy_executor::Spawner;
+use embassy_time::{Duration, Timer};
+use esp_backtrace as _;
+use hakkaa::board::Board;
+use hakkaa::trace::Capture;
+
+// This creates a default app-descriptor required by the esp-idf bootloader.
+// For more information see: <https://docs.espressif.com/projects/esp-idf/en/stable/esp32/api-reference/system/app_image_format.html#application-description>
+esp_bootloader_esp_idf::esp_app_desc!();
+
+#[esp_rtos::main]
+async fn main(_spawner: Spawner) -> ! {
+    let mut board = Board::init();
+
+    // Sample fast enough to see the contacts bouncing.
+    let period = Duration::from_micros(100);
+    let duration = Duration::from_secs(10);
+
+    log::info!("Press SW1 and shake the board to record the bouncing of their contacts.");
+    let inputs = [&board.sw1, &*board.u2.input_mut()];
+    let capture: Capture<1024> = Capture::record(&inputs, period, duration).await;
+
+    // Copy the output between `$timescale` and the last timestamp into a .vcd file for viewing it.
+    capture.dump_vcd(&["sw1", "u2"]);
+
+    loop {
+        Timer::after(Duration::from_secs(3)).await;
+    }
+}
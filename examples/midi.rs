@@ -0,0 +1,64 @@
+#![no_std]
+#![no_main]
+#![deny(
+    clippy::mem_forget,
+    reason = "mem::forget is generally not safe to do with esp_hal types, especially those \
+    holding buffers for the duration of a data transfer."
+)]
+
+use embassy_executor::Spawner;
+use embassy_futures::select::{select, Either};
+use esp_backtrace as _;
+use hakkaa::board::Board;
+use hakkaa::led::Storeys;
+use hakkaa::midi::{self, MidiOutput};
+
+// This creates a default app-descriptor required by the esp-idf bootloader.
+// For more information see: <https://docs.espressif.com/projects/esp-idf/en/stable/esp32/api-reference/system/app_image_format.html#application-description>
+esp_bootloader_esp_idf::esp_app_desc!();
+
+/// The notes of a C major scale, one for every storey.
+const SCALE: [u8; 8] = [60, 62, 64, 65, 67, 69, 71, 72];
+/// The General MIDI percussion note for a crash cymbal.
+const CRASH_CYMBAL: u8 = 49;
+const VELOCITY: u8 = 100;
+
+#[esp_rtos::main]
+async fn main(_spawner: Spawner) -> ! {
+    let mut board = Board::init();
+    let p = board.peripherals;
+
+    // Connect a MIDI socket to GPIO9 as described in the documentation of the midi module.
+    let mut midi = MidiOutput::new(p.uart1, p.gpio9).unwrap();
+    let mut storeys = Storeys::new(board.storey_leds);
+
+    log::info!("Hold SW1 for playing the next note of the scale and shake the board for a crash.");
+
+    let mut step = 0;
+    loop {
+        match select(board.sw1.wait_for_falling_edge(), board.u2.wait_for_shake()).await {
+            Either::First(_) => {
+                let note = SCALE[step];
+                storeys.set_pattern(1 << step);
+                if let Err(e) = midi.note_on(0, note, VELOCITY).await {
+                    log::warn!("sending failed: {:?}", e);
+                }
+
+                board.sw1.wait_for_rising_edge().await;
+                storeys.all_off();
+                if let Err(e) = midi.note_off(0, note).await {
+                    log::warn!("sending failed: {:?}", e);
+                }
+                step = (step + 1) % SCALE.len();
+            }
+            Either::Second(_) => {
+                if let Err(e) = midi
+                    .note_on(midi::PERCUSSION_CHANNEL, CRASH_CYMBAL, VELOCITY)
+                    .await
+                {
+                    log::warn!("sending failed: {:?}", e);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,92 @@
+#![no_std]
+#![no_main]
+#![deny(
+    clippy::mem_forget,
+    reason = "mem::forget is generally not safe to do with esp_hal types, especially those \
+    holding buffers for the duration of a data transfer."
+)]
+
+use embassy_executor::Spawner;
+use embassy_futures::select::{select, Either};
+use embassy_time::{Duration, Instant, Timer};
+use esp_backtrace as _;
+use hakkaa::board::Board;
+use hakkaa::display::{self, Controller, Oled};
+use hakkaa::i2c;
+use hakkaa::led::{Period, Storeys, Ticker, STOREY_LEDS};
+use hakkaa::notify::Escalation;
+
+// This creates a default app-descriptor required by the esp-idf bootloader.
+// For more information see: <https://docs.espressif.com/projects/esp-idf/en/stable/esp32/api-reference/system/app_image_format.html#application-description>
+esp_bootloader_esp_idf::esp_app_desc!();
+
+/// The timer durations to pick from in minutes.
+const MINUTES: [u64; 3] = [5, 15, 25];
+const ITEMS: [&str; 3] = ["5 minutes", "15 minutes", "25 minutes"];
+
+#[esp_rtos::main]
+async fn main(_spawner: Spawner) -> ! {
+    let mut board = Board::init();
+    let p = board.peripherals;
+
+    // Connect an SSD1306 module with SDA to GPIO2 and SCL to GPIO9. Use Controller::Sh1106 for
+    // 1.3" modules.
+    let bus = i2c::open(p.i2c0, p.gpio2, p.gpio9).unwrap();
+    let mut oled = Oled::new(bus, Controller::Ssd1306);
+    if let Err(e) = oled.init().await {
+        log::warn!("no display: {:?}", e);
+    }
+    let mut storeys = Storeys::new(board.storey_leds);
+
+    log::info!("Press SW1 for picking a duration on the display and shake the board to start.");
+
+    loop {
+        // Pick the duration from a menu. SW1 selects the next item and shaking confirms.
+        let mut selected = 0;
+        let minutes = loop {
+            display::render_menu(&mut oled, "Pomodoro", &ITEMS, selected);
+            if let Err(e) = oled.flush().await {
+                log::warn!("updating display failed: {:?}", e);
+            }
+
+            match select(board.sw1.wait_for_falling_edge(), board.u2.wait_for_shake()).await {
+                Either::First(_) => {
+                    selected = (selected + 1) % ITEMS.len();
+                    Timer::after_millis(100).await;
+                }
+                Either::Second(_) => break MINUTES.get(selected).copied().unwrap_or(25),
+            }
+        };
+
+        // Count down while showing the progress on the display and the storey LEDs.
+        let total = Duration::from_secs(minutes * 60);
+        let end = Instant::now() + total;
+        let mut ticker = Ticker::every(Period::from_secs(1));
+        loop {
+            let remaining = end.saturating_duration_since(Instant::now());
+            display::render_timer(&mut oled, "Focus!", remaining, total);
+            if let Err(e) = oled.flush().await {
+                log::warn!("updating display failed: {:?}", e);
+            }
+
+            let elapsed = (total - remaining).as_secs();
+            let lit = elapsed * STOREY_LEDS as u64 / total.as_secs();
+            storeys.set_pattern(0xffu8.checked_shl(lit as u32).map_or(0xff, |mask| !mask));
+
+            if remaining == Duration::from_ticks(0) {
+                break;
+            }
+            ticker.next().await;
+        }
+
+        oled.clear();
+        oled.text(3, 4, "Time is up!");
+        if let Err(e) = oled.flush().await {
+            log::warn!("updating display failed: {:?}", e);
+        }
+        let acknowledged = select(board.sw1.wait_for_falling_edge(), board.u2.wait_for_shake());
+        Escalation::default()
+            .alert(&mut storeys, acknowledged)
+            .await;
+    }
+}
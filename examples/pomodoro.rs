@@ -6,8 +6,9 @@
     holding buffers for the duration of a data transfer."
 )]
 
+use core::future::pending;
 use embassy_executor::Spawner;
-use embassy_futures::select::{select, Either};
+use embassy_futures::select::{select, select3, Either, Either3};
 use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
 use embassy_sync::signal::Signal;
 use embassy_time::{Duration, Timer};
@@ -15,13 +16,17 @@ use esp_backtrace as _;
 
 use esp_hal::gpio::Input;
 use hakkaa::board::Board;
-use hakkaa::led::Storeys;
+use hakkaa::led::{Period, Storeys, Ticker};
+use hakkaa::notify::Escalation;
+use hakkaa::shake::ShakeSensor;
 use hakkaa::switch::LowActiveSwitch;
-
-extern crate alloc;
+use hakkaa::task;
 
 type ButtonSignal = Signal<CriticalSectionRawMutex, ()>;
 
+/// Whether to give a subtle tick on the blue ESP LED every second while focusing.
+const TICKING: bool = true;
+
 // This creates a default app-descriptor required by the esp-idf bootloader.
 // For more information see: <https://docs.espressif.com/projects/esp-idf/en/stable/esp32/api-reference/system/app_image_format.html#application-description>
 esp_bootloader_esp_idf::esp_app_desc!();
@@ -54,10 +59,10 @@ async fn wait_for_button_n_times<'a>(button: &mut Input<'a>, n: usize) {
 }
 
 /// Task waiting for three times an input on `button` and signalling this event through `signal`.
-#[embassy_executor::task(pool_size = 2)]
+#[embassy_executor::task]
 async fn button_task(mut button: Input<'static>, signal: &'static ButtonSignal) {
     loop {
-        wait_for_button(&mut button).await;
+        wait_for_button_n_times(&mut button, 3).await;
         signal.signal(());
     }
 }
@@ -67,23 +72,45 @@ async fn button_task(mut button: Input<'static>, signal: &'static ButtonSignal)
 async fn pomodoro_task(
     mut storeys: Storeys<'static>,
     first_button: &'static ButtonSignal,
+    mut shake: ShakeSensor<'static>,
     mut finished_led: LowActiveSwitch<'static>,
 ) {
-    let step = Duration::from_secs(1);
+    let step = Period::from_secs(1);
     let pomodoro_timer = Duration::from_secs(60 * 25);
     let break_timer = Duration::from_secs(60 * 5);
 
-    // TODO: currently timer starts immediately.
-    log::info!("Pomodoro Timer: Press the button three times to start a 25 minute timer.");
+    log::info!(
+        "Pomodoro Timer: Press the button or shake the board three times to start a 25 minute \
+        timer."
+    );
     first_button.reset();
-    //    first_button.wait().await;
+    let shaken = shake.wait_for_n_shakes::<3>(Duration::from_secs(3));
+    match select(first_button.wait(), shaken).await {
+        Either::First(_) => log::debug!("button pressed"),
+        Either::Second(_) => log::debug!("board shaken"),
+    }
 
-    match select(storeys.cycle(step), timer(pomodoro_timer)).await {
-        Either::First(_) => log::debug!("cycle done"),
-        Either::Second(_) => log::debug!("timer done"),
+    let ticking = async {
+        match TICKING {
+            true => tick(&mut finished_led, step).await,
+            false => pending().await,
+        }
+    };
+    match select3(storeys.cycle(step), ticking, timer(pomodoro_timer)).await {
+        Either3::First(_) => log::debug!("cycle done"),
+        Either3::Second(_) => log::debug!("ticking done"),
+        Either3::Third(_) => log::debug!("timer done"),
     }
+    finished_led.switch_off();
 
-    log::info!("Pomodoro timer finished! Taking a short 5 minute break.",);
+    log::info!("Pomodoro timer finished! Press the button three times or shake the board.");
+    first_button.reset();
+    let acknowledged = select(first_button.wait(), shake.wait_for_shake());
+    Escalation::default()
+        .alert(&mut storeys, acknowledged)
+        .await;
+
+    log::info!("Taking a short 5 minute break.");
     match select(storeys.blink(step), timer(break_timer)).await {
         Either::First(_) => log::debug!("blink done"),
         Either::Second(_) => log::debug!("break timer done"),
@@ -94,6 +121,20 @@ async fn pomodoro_task(
     log::info!("Press Ctrl + C to exit.");
 }
 
+/// Briefly flashes `led` at the start of every `period`. This runs in phase with the storey LEDs
+/// cycling with the same period.
+async fn tick(led: &mut LowActiveSwitch<'_>, period: Period) {
+    let pulse = Duration::from_millis(20);
+    let mut ticker = Ticker::every(period);
+
+    loop {
+        ticker.next().await;
+        led.switch_on();
+        delay(pulse).await;
+        led.switch_off();
+    }
+}
+
 async fn timer(minutes: Duration) {
     Timer::after(minutes).await
 }
@@ -108,22 +149,22 @@ async fn main(spawner: Spawner) -> ! {
 
     log::info!("Starting Pomodoro Timer.");
 
-    // 1. Wait for user to press SW1 button.
+    // 1. Wait for user to press SW1 button or shake the board. (done in pomodoro_task)
     // 2. Start blinking cycle on LEDs for 25 minutes. (done in pomodoro_task)
-    // 3. Blink all LEDs rapidly for 5 minutes. (done in pomodoro_task)
-    // 4. Repeat from 1.
+    // 3. Alert with escalating LED flashes until acknowledged. (done in pomodoro_task)
+    // 4. Blink all LEDs rapidly for 5 minutes. (done in pomodoro_task)
+    // 5. Repeat from 1.
 
     // Press SW1 two times to restart the pomodoro timer.
 
     // Spawn a debouncing and counting task for each "button". Each triplet of "presses" will
     // generate as signal which is later checked by the EOL task.
-    spawner.spawn(button_task(board.sw1, &SW1_SIGNAL)).unwrap();
+    task::spawn_or_report(&spawner, "button", button_task(board.sw1, &SW1_SIGNAL));
 
     // Finally spawn the EOL task showing different storey LED patterns for user inspection of LEDs
     // and as a prompt for pressing SW1 or shaking the board for checking the shake sensor U2.
-    spawner
-        .spawn(pomodoro_task(storeys, &SW1_SIGNAL, board.esp_led))
-        .unwrap();
+    let pomodoro = pomodoro_task(storeys, &SW1_SIGNAL, board.u2, board.esp_led);
+    task::spawn_or_report(&spawner, "pomodoro", pomodoro);
 
     // Keep the main task running forever.
     loop {
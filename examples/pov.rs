@@ -0,0 +1,56 @@
+#![no_std]
+#![no_main]
+#![deny(
+    clippy::mem_forget,
+    reason = "mem::forget is generally not safe to do with esp_hal types, especially those \
+    holding buffers for the duration of a data transfer."
+)]
+
+use embassy_executor::Spawner;
+use embassy_time::{Duration, Timer};
+use embedded_graphics::{
+    mono_font::{ascii::FONT_5X7, MonoTextStyle},
+    pixelcolor::BinaryColor,
+    prelude::*,
+    text::{Baseline, Text},
+};
+use esp_backtrace as _;
+use hakkaa::board::Board;
+use hakkaa::graphics::Frame;
+use hakkaa::led::{Storeys, STOREY_LEDS};
+
+// This creates a default app-descriptor required by the esp-idf bootloader.
+// For more information see: <https://docs.espressif.com/projects/esp-idf/en/stable/esp32/api-reference/system/app_image_format.html#application-description>
+esp_bootloader_esp_idf::esp_app_desc!();
+
+const MESSAGE: &str = "Hakkaa";
+const WIDTH: usize = 5 * MESSAGE.len();
+
+#[esp_rtos::main]
+async fn main(_spawner: Spawner) -> ! {
+    let board = Board::init();
+
+    let column_delay = Duration::from_millis(2);
+    let image_delay = Duration::from_millis(30);
+    let mut storeys = Storeys::new(board.storey_leds);
+
+    // Render the message once with the bottom of the font aligned to the bottom storey.
+    let mut frame = Frame::<WIDTH, STOREY_LEDS>::new();
+    let style = MonoTextStyle::new(&FONT_5X7, BinaryColor::On);
+    let Ok(_) =
+        Text::with_baseline(MESSAGE, Point::new(0, 1), style, Baseline::Top).draw(&mut frame);
+
+    log::info!("Wave the board from left to right to read the message.");
+
+    // Display the message column by column while the board is moving. The text can only be read
+    // in one direction as it is not symmetrical like the smile.
+    loop {
+        for x in 0..WIDTH {
+            storeys.set_pattern(frame.column(x));
+            Timer::after(column_delay).await;
+        }
+
+        storeys.all_off();
+        Timer::after(image_delay).await;
+    }
+}
@@ -0,0 +1,89 @@
+#![no_std]
+#![no_main]
+#![deny(
+    clippy::mem_forget,
+    reason = "mem::forget is generally not safe to do with esp_hal types, especially those \
+    holding buffers for the duration of a data transfer."
+)]
+
+use embassy_executor::Spawner;
+use esp_backtrace as _;
+use esp_hal::analog::adc::{Adc, AdcConfig, AdcPin, Attenuation};
+use esp_hal::peripherals::{ADC1, GPIO2};
+use esp_hal::Async;
+use hakkaa::board::Board;
+use hakkaa::led::Period;
+use hakkaa::sensor::{self, ChipTemperature, Poller, Sensor, Value};
+use hakkaa::task;
+
+// This creates a default app-descriptor required by the esp-idf bootloader.
+// For more information see: <https://docs.espressif.com/projects/esp-idf/en/stable/esp32/api-reference/system/app_image_format.html#application-description>
+esp_bootloader_esp_idf::esp_app_desc!();
+
+/// The full scale of the 12 bit ADC readings.
+const FULL_SCALE: u32 = 4095;
+/// The voltage at full scale with 11 dB attenuation. This is a rough value, the actual one varies
+/// from chip to chip.
+const FULL_SCALE_MILLIVOLTS: u32 = 2500;
+
+/// A voltage measured at GPIO2, for example from the wiper of a potentiometer.
+struct Voltage<'a> {
+    adc: Adc<'a, ADC1<'a>, Async>,
+    pin: AdcPin<GPIO2<'a>, ADC1<'a>>,
+}
+
+impl Sensor for Voltage<'_> {
+    fn name(&self) -> &'static str {
+        "gpio2"
+    }
+
+    async fn sample(&mut self) -> Option<Value> {
+        let reading = u32::from(self.adc.read_oneshot(&mut self.pin).await);
+        Some(Value::Voltage(reading * FULL_SCALE_MILLIVOLTS / FULL_SCALE))
+    }
+}
+
+/// Logs all readings published. A telemetry uplink would subscribe for them in the same way.
+#[embassy_executor::task]
+async fn logger_task(mut readings: sensor::Subscriber) {
+    loop {
+        let reading = readings.next_message_pure().await;
+        log::info!(
+            "{} @ {} ms: {:?}",
+            reading.sensor,
+            reading.at.as_millis(),
+            reading.value
+        );
+    }
+}
+
+#[esp_rtos::main]
+async fn main(spawner: Spawner) -> ! {
+    let board = Board::init();
+    let p = board.peripherals;
+
+    let mut chip = ChipTemperature::new(p.tsens).unwrap();
+
+    let mut config = AdcConfig::new();
+    let pin = config.enable_pin(p.gpio2, Attenuation::_11dB);
+    let adc = Adc::new(p.adc1, config).into_async();
+    let mut voltage = Voltage { adc, pin };
+
+    if let Some(readings) = sensor::subscribe() {
+        task::spawn_or_report(&spawner, "logger", logger_task(readings));
+    }
+
+    // Sample the slowly changing chip temperature every five seconds and the voltage at GPIO2
+    // twice a second.
+    let mut poller = Poller::<2>::new();
+    let chip_slot = poller.add(Period::from_secs(5)).unwrap();
+    let voltage_slot = poller.add(Period::from_millis(500)).unwrap();
+
+    loop {
+        match poller.next().await {
+            slot if slot == chip_slot => sensor::poll(&mut chip).await,
+            slot if slot == voltage_slot => sensor::poll(&mut voltage).await,
+            _ => None,
+        };
+    }
+}
@@ -0,0 +1,53 @@
+#![no_std]
+#![no_main]
+#![deny(
+    clippy::mem_forget,
+    reason = "mem::forget is generally not safe to do with esp_hal types, especially those \
+    holding buffers for the duration of a data transfer."
+)]
+
+use embassy_executor::Spawner;
+use embassy_futures::select::{select, Either};
+use esp_backtrace as _;
+use esp_hal::ledc::{channel, timer};
+use hakkaa::actuator::{self, PwmOut, Servo};
+use hakkaa::board::Board;
+use hakkaa::led::{Storeys, STOREY_LEDS};
+
+// This creates a default app-descriptor required by the esp-idf bootloader.
+// For more information see: <https://docs.espressif.com/projects/esp-idf/en/stable/esp32/api-reference/system/app_image_format.html#application-description>
+esp_bootloader_esp_idf::esp_app_desc!();
+
+/// The angle to turn the servo by on every button press.
+const STEP: u8 = 30;
+
+#[esp_rtos::main]
+async fn main(_spawner: Spawner) -> ! {
+    let mut board = Board::init();
+    let p = board.peripherals;
+
+    // Connect the signal input of a servo to GPIO2. Power it from a separate 5 V supply with a
+    // common ground, the board can't supply the current of its motor.
+    let ledc = actuator::ledc(p.ledc);
+    let timer = actuator::timer(&ledc, timer::Number::Timer0, Servo::FREQUENCY).unwrap();
+    let pwm = PwmOut::new(&ledc, &timer, channel::Number::Channel0, p.gpio2).unwrap();
+    let mut servo = Servo::new(pwm);
+    let mut storeys = Storeys::new(board.storey_leds);
+
+    log::info!("Press SW1 for turning the servo and shake the board for centering it.");
+
+    let mut angle = Servo::MAX_ANGLE / 2;
+    loop {
+        servo.set_angle(angle);
+        log::info!("angle: {}°", angle);
+
+        let storey = usize::from(angle) * (STOREY_LEDS - 1) / usize::from(Servo::MAX_ANGLE);
+        storeys.set_pattern(1 << storey);
+
+        angle = match select(board.sw1.wait_for_falling_edge(), board.u2.wait_for_shake()).await {
+            Either::First(_) if angle >= Servo::MAX_ANGLE => 0,
+            Either::First(_) => angle.saturating_add(STEP).min(Servo::MAX_ANGLE),
+            Either::Second(_) => Servo::MAX_ANGLE / 2,
+        };
+    }
+}
@@ -6,17 +6,17 @@
     holding buffers for the duration of a data transfer."
 )]
 
-use adjacent_pair_iterator::AdjacentPairIterator;
 use embassy_executor::Spawner;
 use embassy_futures::select::{select, Either};
 use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
 use embassy_sync::signal::Signal;
 use embassy_time::{Duration, Instant, Timer};
 use esp_backtrace as _;
-use esp_hal::gpio::Input;
 use hakkaa::board::Board;
 use hakkaa::led::Storeys;
-use heapless::HistoryBuffer;
+use hakkaa::shake::ShakeSensor;
+use hakkaa::task;
+use heapless::HistoryBuf;
 
 type DurationSignal = Signal<CriticalSectionRawMutex, Duration>;
 
@@ -56,45 +56,35 @@ static SMILE_PATTERN: [u8; 26] = [
     0b00000000,
 ];
 
-async fn delay(duration: Duration) {
-    Timer::after(duration).await;
-}
-
 /// Determines the shake duration. This is the duration between the first falling edges of the
 /// pulse trains generated when the shake sensor activates (you can hear its click sound).
-#[esp_hal_embassy::main]
-async fn shake_period(mut sensor: Input<'static>, signal: &'static DurationSignal) {
-    let debounce_delay = Duration::from_millis(150);
-    let mut history = HistoryBuffer::<_, 6>::new();
+#[embassy_executor::task]
+async fn shake_period(mut sensor: ShakeSensor<'static>, signal: &'static DurationSignal) {
+    let mut shakes = sensor.events();
+    let mut history = HistoryBuf::<Duration, 5>::new();
 
     loop {
-        // Wait for a falling edge. When shaking, this is likely the first one from an end
-        // position.
-        sensor.wait_for_falling_edge().await;
+        // Wait for the next shake. When shaking, this is likely the first activation at an end
+        // position. The shake sensor will bounce (on and off) several times at when it activates
+        // but this gets already taken care of by the sensor's debouncing.
+        let shake = shakes.next().await;
 
-        // Record the current timestamp and store it into the history for computing an average
+        // Store the time since the previous shake into the history for computing an average
         // shake period from.
-        let now = Instant::now();
-        history.write(now);
+        if let Some(since_previous) = shake.since_previous {
+            history.write(since_previous);
+        }
 
         // Actually compute the average shake period and signal it to others (if there is one)
         let sum: Option<Duration> = history
             .oldest_ordered()
-            .adjacent_pairs()
-            .map(|(older, younger)| {
-                let duration = younger.checked_duration_since(*older);
-                duration.unwrap()
-            })
+            .copied()
             .reduce(|acc, duration| acc + duration);
         if let Some(sum) = sum {
-            let count = history.len().checked_sub(1).unwrap().try_into().unwrap();
+            let count = history.len().try_into().unwrap();
             let mean = sum / count;
             signal.signal(mean);
         }
-
-        // The shake sensor will bounce (on and off) several times at when it activates. Try to
-        // ignore bouncing after the first edge by just sitting tight and waiting.
-        delay(debounce_delay).await;
     }
 }
 
@@ -119,12 +109,11 @@ async fn display_pattern_back_and_forth<'a>(
         // Update the storey LEDs for the current row.
         storeys.set_pattern(*row);
 
-        // Delay for the remaining time for this row.
+        // Delay until the end of this row. Waiting for this deadline doesn't underflow if we are
+        // already running late.
         let row: u32 = index.try_into().unwrap();
-        let row_start = pattern_start + row * row_duration;
-        let in_row = Instant::now().duration_since(row_start);
-        let remaining = row_duration - in_row;
-        delay(remaining).await;
+        let row_end = pattern_start + (row + 1) * row_duration;
+        Timer::at(row_end).await;
     }
 }
 
@@ -144,9 +133,8 @@ async fn main(spawner: Spawner) -> ! {
     let mut storeys = Storeys::new(board.storey_leds);
 
     // Spawn a task for concurrently determining the shake period.
-    spawner
-        .spawn(shake_period(board.u2, &SHAKE_PERIOD_SIGNAL))
-        .unwrap();
+    let shake = shake_period(board.u2, &SHAKE_PERIOD_SIGNAL);
+    task::spawn_or_report(&spawner, "shake period", shake);
 
     loop {
         // Create the futures for cycling the pattern and waiting for the next shake signal. They
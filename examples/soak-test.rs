@@ -0,0 +1,48 @@
+#![no_std]
+#![no_main]
+#![deny(
+    clippy::mem_forget,
+    reason = "mem::forget is generally not safe to do with esp_hal types, especially those \
+    holding buffers for the duration of a data transfer."
+)]
+
+use embassy_executor::Spawner;
+use embassy_time::{Duration, Timer};
+use esp_backtrace as _;
+use hakkaa::board::Board;
+use hakkaa::eol;
+use hakkaa::led::{Period, Storeys};
+use hakkaa::sensor::ChipTemperature;
+
+// This creates a default app-descriptor required by the esp-idf bootloader.
+// For more information see: <https://docs.espressif.com/projects/esp-idf/en/stable/esp32/api-reference/system/app_image_format.html#application-description>
+esp_bootloader_esp_idf::esp_app_desc!();
+
+/// How long to run the soak test.
+const HOURS: u64 = 8;
+
+#[esp_rtos::main]
+async fn main(_spawner: Spawner) -> ! {
+    let board = Board::init();
+
+    let mut chip = ChipTemperature::new(board.peripherals.tsens).unwrap();
+    let mut storeys = Storeys::new(board.storey_leds);
+    let mut finished_led = board.esp_led;
+
+    let passed = eol::soak(&mut storeys, &mut chip, HOURS).await;
+
+    // Done. Light up all storey LEDs on success and additionally the blue LED on the ESP board.
+    // Otherwise just blink.
+    if passed {
+        log::info!("Soak test passed.");
+        storeys.all_on();
+        finished_led.switch_on();
+    } else {
+        log::error!("Soak test failed. See warnings above.");
+        storeys.blink(Period::from_millis(250)).await;
+    }
+
+    loop {
+        Timer::after(Duration::from_secs(3)).await;
+    }
+}
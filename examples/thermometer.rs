@@ -0,0 +1,67 @@
+#![no_std]
+#![no_main]
+#![deny(
+    clippy::mem_forget,
+    reason = "mem::forget is generally not safe to do with esp_hal types, especially those \
+    holding buffers for the duration of a data transfer."
+)]
+
+use embassy_executor::Spawner;
+use esp_backtrace as _;
+use hakkaa::board::Board;
+use hakkaa::led::{Period, Storeys, STOREY_LEDS};
+use hakkaa::onewire::{Ds18b20, OneWire};
+use hakkaa::sensor::{self, ChipTemperature, Poller, Value};
+
+// This creates a default app-descriptor required by the esp-idf bootloader.
+// For more information see: <https://docs.espressif.com/projects/esp-idf/en/stable/esp32/api-reference/system/app_image_format.html#application-description>
+esp_bootloader_esp_idf::esp_app_desc!();
+
+/// The temperature shown with no storey lit.
+const BASE_CELSIUS: f32 = 18.0;
+/// The temperature range covered by a single storey LED.
+const CELSIUS_PER_STOREY: f32 = 1.5;
+
+/// Returns a bar graph pattern for `celsius`.
+fn bar(celsius: f32) -> u8 {
+    let lit = ((celsius - BASE_CELSIUS) / CELSIUS_PER_STOREY).clamp(0.0, STOREY_LEDS as f32);
+    0xffu8.checked_shl(lit as u32).map_or(0xff, |mask| !mask)
+}
+
+#[esp_rtos::main]
+async fn main(_spawner: Spawner) -> ! {
+    let board = Board::init();
+    let p = board.peripherals;
+
+    // Connect the data line of a DS18B20 to GPIO2 with a 4.7 kΩ pull-up to 3V3.
+    let mut bus = OneWire::new(p.gpio2);
+    match bus.search::<4>() {
+        Ok(found) => log::info!("1-Wire devices: {:x?}", found),
+        Err(e) => log::warn!("searching 1-Wire devices failed: {:?}", e),
+    }
+    let mut probe = Ds18b20::new(bus);
+    let mut chip = ChipTemperature::new(p.tsens).unwrap();
+    let mut storeys = Storeys::new(board.storey_leds);
+
+    let mut poller = Poller::<2>::new();
+    let probe_slot = poller.add(Period::from_secs(2)).unwrap();
+    let chip_slot = poller.add(Period::from_secs(10)).unwrap();
+
+    loop {
+        let slot = poller.next().await;
+        let reading = if slot == probe_slot {
+            sensor::poll(&mut probe).await
+        } else if slot == chip_slot {
+            sensor::poll(&mut chip).await
+        } else {
+            None
+        };
+
+        if let Some(reading) = reading {
+            log::info!("{}: {:?}", reading.sensor, reading.value);
+            if let (true, Value::Temperature(celsius)) = (slot == probe_slot, reading.value) {
+                storeys.set_pattern(bar(celsius));
+            }
+        }
+    }
+}
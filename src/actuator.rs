@@ -0,0 +1,230 @@
+//! PWM outputs and hobby servos on the spare pins _GPIO2_ and _GPIO9_, driven by the LED PWM
+//! controller (LEDC).
+//!
+//! The LEDC has four timers generating the PWM frequency and six channels which can each be
+//! attached to a timer and a pin. All channels on the same timer share its frequency. [`timer()`]
+//! sets up a timer with the best duty resolution available and [`PwmOut`] is a channel on top of
+//! it. [`Servo`] turns a 50 Hz [`PwmOut`] into a signal for a standard hobby servo.
+//!
+//! ```rust
+//! let ledc = actuator::ledc(board.peripherals.ledc);
+//! let timer = actuator::timer(&ledc, timer::Number::Timer0, Servo::FREQUENCY)?;
+//! let pwm = PwmOut::new(&ledc, &timer, channel::Number::Channel0, board.peripherals.gpio2)?;
+//!
+//! let mut servo = Servo::new(pwm);
+//! servo.set_angle(90);
+//! ```
+//!
+//! Mind that _GPIO9_ is a strapping pin which needs to be high during reset. Connect anything
+//! pulling it low, like a servo's signal input, to _GPIO2_.
+
+use core::convert::Infallible;
+use core::fmt;
+
+use embassy_time::Duration;
+use embedded_hal::pwm::{ErrorType, SetDutyCycle};
+use esp_hal::gpio::interconnect::PeripheralOutput;
+use esp_hal::gpio::DriveMode;
+use esp_hal::ledc::channel::{self, ChannelHW, ChannelIFace};
+use esp_hal::ledc::timer::{self, config::Duty, LSClockSource, TimerIFace};
+use esp_hal::ledc::{LSGlobalClkSource, Ledc, LowSpeed};
+use esp_hal::peripherals::LEDC;
+use esp_hal::time::Rate;
+
+/// The clock the LEDC timers get derived from.
+const SOURCE_CLOCK_HZ: u32 = 80_000_000;
+/// The highest duty resolution supported by the ESP32-C3.
+const MAX_DUTY_BITS: u32 = 14;
+
+/// Errors from setting up PWM outputs.
+#[derive(Debug)]
+pub enum Error {
+    /// The frequency is too high for getting at least a single bit of duty resolution.
+    Frequency,
+    /// Configuring the timer failed, for example because the frequency is too low.
+    Timer(timer::Error),
+    /// Configuring the channel failed.
+    Channel(channel::Error),
+}
+
+impl From<timer::Error> for Error {
+    fn from(e: timer::Error) -> Self {
+        Self::Timer(e)
+    }
+}
+
+impl From<channel::Error> for Error {
+    fn from(e: channel::Error) -> Self {
+        Self::Channel(e)
+    }
+}
+
+/// Takes the LEDC peripheral and clocks it from the APB clock.
+pub fn ledc(ledc: LEDC<'_>) -> Ledc<'_> {
+    let mut ledc = Ledc::new(ledc);
+    ledc.set_global_slow_clock(LSGlobalClkSource::APBClk);
+    ledc
+}
+
+/// Configures the timer `number` for generating `frequency` with the highest possible duty
+/// resolution.
+pub fn timer<'a>(
+    ledc: &Ledc<'a>,
+    number: timer::Number,
+    frequency: Rate,
+) -> Result<timer::Timer<'a, LowSpeed>, Error> {
+    let ratio = SOURCE_CLOCK_HZ
+        .checked_div(frequency.as_hz())
+        .ok_or(Error::Frequency)?;
+    let bits = ratio.checked_ilog2().unwrap_or(0).min(MAX_DUTY_BITS);
+    let duty = Duty::try_from(bits).map_err(|_| Error::Frequency)?;
+
+    let mut timer = ledc.timer::<LowSpeed>(number);
+    timer.configure(timer::config::Config {
+        duty,
+        clock_source: LSClockSource::APBClk,
+        frequency,
+    })?;
+
+    Ok(timer)
+}
+
+/// A PWM output on a pin, running at the frequency of its timer.
+///
+/// It implements [`SetDutyCycle`] from embedded-hal for use with generic drivers.
+pub struct PwmOut<'a> {
+    channel: channel::Channel<'a, LowSpeed>,
+    /// The maximum duty value, which corresponds to the output being high all the time.
+    max_duty: u32,
+    /// The length of a PWM period in nanoseconds.
+    period_nanos: u64,
+}
+
+impl<'a> PwmOut<'a> {
+    /// Creates a new `PwmOut` from channel `number` on `timer`, driving `pin`. The output starts
+    /// low.
+    pub fn new(
+        ledc: &Ledc<'a>,
+        timer: &'a timer::Timer<'a, LowSpeed>,
+        number: channel::Number,
+        pin: impl PeripheralOutput<'a>,
+    ) -> Result<Self, Error> {
+        let mut channel = ledc.channel(number, pin);
+        channel.configure(channel::config::Config {
+            timer,
+            duty_pct: 0,
+            drive_mode: DriveMode::PushPull,
+        })?;
+
+        let bits = timer.duty().map_or(0, |duty| duty as u32);
+        let period_nanos = 1_000_000_000u64
+            .checked_div(u64::from(timer.frequency()))
+            .ok_or(Error::Timer(timer::Error::FrequencyUnset))?;
+
+        Ok(Self {
+            channel,
+            max_duty: 1 << bits,
+            period_nanos,
+        })
+    }
+
+    /// Sets the duty cycle to `duty_pct` percent. Values above 100 are clamped.
+    pub fn set_duty_pct(&mut self, duty_pct: u8) {
+        let duty = u32::from(duty_pct.min(100)) * self.max_duty / 100;
+        self.channel.set_duty_hw(duty);
+    }
+
+    /// Sets the time the output is high during every period. Widths longer than the period are
+    /// clamped.
+    pub fn set_pulse_width(&mut self, width: Duration) {
+        let width_nanos = width.as_micros().saturating_mul(1_000);
+        let duty = width_nanos.saturating_mul(u64::from(self.max_duty)) / self.period_nanos;
+        self.channel
+            .set_duty_hw(u32::try_from(duty).unwrap_or(u32::MAX).min(self.max_duty));
+    }
+
+    /// The length of a PWM period.
+    pub fn period(&self) -> Duration {
+        Duration::from_micros(self.period_nanos / 1_000)
+    }
+}
+
+// The LEDC channel does not implement `Debug`.
+impl fmt::Debug for PwmOut<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PwmOut")
+            .field("max_duty", &self.max_duty)
+            .field("period_nanos", &self.period_nanos)
+            .finish_non_exhaustive()
+    }
+}
+
+impl ErrorType for PwmOut<'_> {
+    type Error = Infallible;
+}
+
+impl SetDutyCycle for PwmOut<'_> {
+    fn max_duty_cycle(&self) -> u16 {
+        // The duty resolution is limited to 14 bits, so this always fits.
+        u16::try_from(self.max_duty).unwrap_or(u16::MAX)
+    }
+
+    fn set_duty_cycle(&mut self, duty: u16) -> Result<(), Self::Error> {
+        self.channel.set_duty_hw(u32::from(duty).min(self.max_duty));
+        Ok(())
+    }
+}
+
+/// A standard hobby servo controlled by the width of pulses sent every 20 ms.
+///
+/// Most servos turn to their center position at 1.5 ms and cover their range of about 180 degrees
+/// between 1 ms and 2 ms. Many of them go beyond this, so the range can be adjusted with
+/// [`Servo::with_range`].
+#[derive(Debug)]
+pub struct Servo<'a> {
+    pwm: PwmOut<'a>,
+    min: Duration,
+    max: Duration,
+}
+
+impl<'a> Servo<'a> {
+    /// The PWM frequency servos expect.
+    pub const FREQUENCY: Rate = Rate::from_hz(50);
+    /// The pulse width for the lowest angle.
+    pub const DEFAULT_MIN: Duration = Duration::from_micros(1_000);
+    /// The pulse width for the highest angle.
+    pub const DEFAULT_MAX: Duration = Duration::from_micros(2_000);
+    /// The highest angle in degrees.
+    pub const MAX_ANGLE: u8 = 180;
+
+    /// Creates a new `Servo` driven by `pwm`, which needs to run at [`Servo::FREQUENCY`].
+    pub fn new(pwm: PwmOut<'a>) -> Self {
+        Self {
+            pwm,
+            min: Self::DEFAULT_MIN,
+            max: Self::DEFAULT_MAX,
+        }
+    }
+
+    /// Sets the pulse widths for the lowest and the highest angle.
+    pub fn with_range(self, min: Duration, max: Duration) -> Self {
+        Self { min, max, ..self }
+    }
+
+    /// Returns the underlying PWM output.
+    pub fn free(self) -> PwmOut<'a> {
+        self.pwm
+    }
+
+    /// Turns the servo to `degrees` from the lowest angle, up to [`Servo::MAX_ANGLE`].
+    pub fn set_angle(&mut self, degrees: u8) {
+        let span = self.max.as_micros().saturating_sub(self.min.as_micros());
+        let offset = span * u64::from(degrees.min(Self::MAX_ANGLE)) / u64::from(Self::MAX_ANGLE);
+        self.set_pulse(self.min + Duration::from_micros(offset));
+    }
+
+    /// Sends pulses of `width`, clamped to the range of the servo.
+    pub fn set_pulse(&mut self, width: Duration) {
+        self.pwm.set_pulse_width(width.max(self.min).min(self.max));
+    }
+}
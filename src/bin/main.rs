@@ -23,7 +23,7 @@ fn main() -> ! {
     let config = esp_hal::Config::default().with_cpu_clock(CpuClock::max());
     let _peripherals = esp_hal::init(config);
 
-    log::info!("Hello world!");
+    log::info!("Hello world from hakkaa {}!", hakkaa::version());
 
     // We are required not to return when declaring our entry point with #[main].
     loop {}
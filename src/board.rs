@@ -1,13 +1,110 @@
 //! Board support for the Hakkaa board.
 
 use esp_hal::clock::CpuClock;
+use esp_hal::delay::Delay;
+use esp_hal::efuse::Efuse;
 use esp_hal::gpio::{DriveMode, Input, InputConfig, Level, Output, OutputConfig, Pull};
 use esp_hal::interrupt::software::SoftwareInterruptControl;
+use esp_hal::peripherals::{
+    ADC1, ADC2, AES, BT, DMA, DMA_CH0, DMA_CH1, DMA_CH2, DS, FLASH, GPIO2, GPIO9, GPIO_SD, HMAC,
+    I2C0, I2S0, LEDC, RMT, RNG, RSA, SHA, SPI2, TIMG0, TIMG1, TSENS, TWAI0, UART1, UHCI0, WIFI,
+};
 use esp_hal::timer::systimer::SystemTimer;
 
 use crate::led::STOREY_LEDS;
+use crate::shake::ShakeSensor;
 use crate::switch::LowActiveSwitch;
 
+/// Provides the timestamps for log output: milliseconds since boot.
+#[cfg(feature = "log-timestamp")]
+#[no_mangle]
+fn _esp_println_timestamp() -> u64 {
+    esp_hal::time::Instant::now()
+        .duration_since_epoch()
+        .as_millis()
+}
+
+/// Returns an identifier unique to each board. This is the factory-programmed base MAC address of
+/// the ESP32-C3.
+pub fn device_id() -> [u8; 6] {
+    Efuse::read_base_mac_address()
+}
+
+/// The ESP32-C3 peripherals not used by the board support, for building on what the wrappers in
+/// this crate don't cover yet.
+///
+/// Some peripherals are withheld as they are in use behind the scenes:
+///
+/// - The pins of the board resources in [`Board`].
+/// - The USB pins _GPIO18_ and _GPIO19_, `USB_DEVICE` and `UART0`, which carry the log output and
+///   are used for flashing.
+/// - `SYSTIMER` and `SW_INTERRUPT`, which drive the scheduler, and `LPWR`, which
+///   [`crate::system`] takes for rebooting and shutting down.
+/// - `SPI0` and `SPI1`, which access the flash and RAM, and register blocks esp-hal only uses
+///   internally, like `SYSTEM`, `IO_MUX` or `EFUSE`.
+#[derive(Debug)]
+pub struct Peripherals<'a> {
+    /// The unused pin _GPIO2_.
+    pub gpio2: GPIO2<'a>,
+    /// The unused pin _GPIO9_. This is a strapping pin and needs to be high during reset for
+    /// booting the firmware.
+    pub gpio9: GPIO9<'a>,
+    /// The analog-to-digital converter, for example for reading analog signals on _GPIO2_.
+    pub adc1: ADC1<'a>,
+    /// The second analog-to-digital converter. None of its channels is on a spare pin.
+    pub adc2: ADC2<'a>,
+    /// The AES accelerator.
+    pub aes: AES<'a>,
+    /// The Bluetooth LE radio, for use with esp-radio.
+    pub bt: BT<'a>,
+    /// The general purpose DMA controller.
+    pub dma: DMA<'a>,
+    /// The first DMA channel, for example for SPI2 transfers.
+    pub dma_ch0: DMA_CH0<'a>,
+    /// The second DMA channel.
+    pub dma_ch1: DMA_CH1<'a>,
+    /// The third DMA channel.
+    pub dma_ch2: DMA_CH2<'a>,
+    /// The digital signature peripheral.
+    pub ds: DS<'a>,
+    /// The SPI flash, for storing data, for example with esp-storage.
+    pub flash: FLASH<'a>,
+    /// The sigma-delta modulator.
+    pub gpio_sd: GPIO_SD<'a>,
+    /// The HMAC accelerator.
+    pub hmac: HMAC<'a>,
+    /// The I2C controller.
+    pub i2c0: I2C0<'a>,
+    /// The I2S controller, for example for digital microphones or audio codecs.
+    pub i2s0: I2S0<'a>,
+    /// The LED PWM controller, for example for dimming LEDs or driving servos.
+    pub ledc: LEDC<'a>,
+    /// The remote control peripheral for generating and capturing pulse trains.
+    pub rmt: RMT<'a>,
+    /// The hardware random number generator.
+    pub rng: RNG<'a>,
+    /// The RSA accelerator.
+    pub rsa: RSA<'a>,
+    /// The SHA accelerator.
+    pub sha: SHA<'a>,
+    /// The general purpose SPI controller.
+    pub spi2: SPI2<'a>,
+    /// The first timer group.
+    pub timg0: TIMG0<'a>,
+    /// The second timer group.
+    pub timg1: TIMG1<'a>,
+    /// The internal temperature sensor of the ESP32-C3.
+    pub tsens: TSENS<'a>,
+    /// The TWAI (CAN) controller.
+    pub twai0: TWAI0<'a>,
+    /// The secondary UART.
+    pub uart1: UART1<'a>,
+    /// The UART DMA controller.
+    pub uhci0: UHCI0<'a>,
+    /// The Wi-Fi radio, for use with esp-radio.
+    pub wifi: WIFI<'a>,
+}
+
 /// Hakkaa board resources.
 pub struct Board<'a> {
     /// The outputs for driving the storey LEDs _D1_ to _D8_ on the main board.
@@ -16,8 +113,10 @@ pub struct Board<'a> {
     pub esp_led: LowActiveSwitch<'a>,
     /// The input the push putton _SW1_ on the main board is connected to.
     pub sw1: Input<'a>,
-    /// The input the shake sensor _U2_ on the main board is connected to.
-    pub u2: Input<'a>,
+    /// The shake sensor _U2_ on the main board.
+    pub u2: ShakeSensor<'a>,
+    /// The remaining peripherals for direct use with esp-hal.
+    pub peripherals: Peripherals<'a>,
 }
 
 impl<'a> Board<'a> {
@@ -32,12 +131,19 @@ impl<'a> Board<'a> {
 
         let config = esp_hal::Config::default().with_cpu_clock(CpuClock::max());
         let peripherals = esp_hal::init(config);
+        log::info!("hakkaa {}", crate::version());
+        for (name, version) in crate::version().dependencies() {
+            log::info!("  {} {}", name, version);
+        }
 
-        esp_alloc::heap_allocator!(size: 64 * 1024);
+        #[cfg(feature = "alloc")]
+        esp_alloc::heap_allocator!(size: crate::config::HEAP_SIZE);
 
         let timer = SystemTimer::new(peripherals.SYSTIMER);
         let sw_int = SoftwareInterruptControl::new(peripherals.SW_INTERRUPT);
         esp_rtos::start(timer.alarm0, sw_int.software_interrupt0);
+        crate::system::init(peripherals.LPWR);
+
         // Storey LEDs and the LED on the ESP32-C3 board are connected as follows: Current flows
         // from the power source (+) directly through the LED, a current limiting resistor, and
         // finally into a GPIO pin with its low-side switching transistor to GND (-).
@@ -101,13 +207,73 @@ impl<'a> Board<'a> {
         let sw1 = Input::new(peripherals.GPIO1, switch_pin_config);
         let u2 = Input::new(peripherals.GPIO0, switch_pin_config);
 
-        // TODO: Re-expose the remaining peripherals (through a board-specific) peripherals struct.
+        // Both inputs are pulled up and only get pulled low while SW1 is pressed or U2 is shaken.
+        // Give the pull-ups some time and check for inputs stuck low, for example from a solder
+        // bridge.
+        Delay::new().delay_micros(INPUT_SETTLING_MICROS);
+        check_idle("SW1", &sw1);
+        check_idle("U2", &u2);
+        let u2 = ShakeSensor::new(u2);
+
+        let peripherals = Peripherals {
+            gpio2: peripherals.GPIO2,
+            gpio9: peripherals.GPIO9,
+            adc1: peripherals.ADC1,
+            adc2: peripherals.ADC2,
+            aes: peripherals.AES,
+            bt: peripherals.BT,
+            dma: peripherals.DMA,
+            dma_ch0: peripherals.DMA_CH0,
+            dma_ch1: peripherals.DMA_CH1,
+            dma_ch2: peripherals.DMA_CH2,
+            ds: peripherals.DS,
+            flash: peripherals.FLASH,
+            gpio_sd: peripherals.GPIO_SD,
+            hmac: peripherals.HMAC,
+            i2c0: peripherals.I2C0,
+            i2s0: peripherals.I2S0,
+            ledc: peripherals.LEDC,
+            rmt: peripherals.RMT,
+            rng: peripherals.RNG,
+            rsa: peripherals.RSA,
+            sha: peripherals.SHA,
+            spi2: peripherals.SPI2,
+            timg0: peripherals.TIMG0,
+            timg1: peripherals.TIMG1,
+            tsens: peripherals.TSENS,
+            twai0: peripherals.TWAI0,
+            uart1: peripherals.UART1,
+            uhci0: peripherals.UHCI0,
+            wifi: peripherals.WIFI,
+        };
 
         Board {
             storey_leds,
             esp_led,
             sw1,
             u2,
+            peripherals,
         }
     }
+
+    /// Returns the remaining peripherals for direct use with esp-hal.
+    ///
+    /// This is the same as accessing [`Board::peripherals`]. Move the peripherals out of this
+    /// field for handing them over to drivers or tasks for good.
+    pub fn peripherals_mut(&mut self) -> &mut Peripherals<'a> {
+        &mut self.peripherals
+    }
+}
+
+/// The time for the pull-ups to bring idle inputs to high level after configuring them.
+const INPUT_SETTLING_MICROS: u32 = 10;
+
+/// Warns if `input` is not at its idle high level. This hints at a switch held down or shorted.
+fn check_idle(name: &str, input: &Input) {
+    if input.is_low() {
+        log::warn!(
+            "{} is low at boot, it might be held down or shorted to GND",
+            name
+        );
+    }
 }
@@ -0,0 +1,4 @@
+//! Field buses for embedding the board into larger systems through transceivers on the spare
+//! pins.
+
+pub mod can;
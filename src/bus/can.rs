@@ -0,0 +1,103 @@
+//! The TWAI (CAN) controller with a transceiver attached to the spare pins.
+//!
+//! The ESP32-C3 only provides the TWAI controller. Connecting to an actual bus requires an
+//! external 3.3 V transceiver like the SN65HVD230.
+//!
+//! ```rust
+//! let id = StandardId::new(0x100).unwrap();
+//! let mut config = can::configure(p.twai0, p.gpio2, p.gpio9, BaudRate::B125K);
+//! config.set_filter(can::accept_only(id));
+//! let mut can = Can::new(config);
+//!
+//! can.send(id, &[0x55]).await?;
+//! let frame = can.receive().await?;
+//! ```
+
+use esp_hal::peripherals::{GPIO2, GPIO9, TWAI0};
+use esp_hal::twai::filter::SingleStandardFilter;
+use esp_hal::twai::{
+    BaudRate, EspTwaiError, EspTwaiFrame, Id, StandardId, Twai, TwaiConfiguration, TwaiMode,
+};
+use esp_hal::Async;
+
+/// The maximum number of data bytes in a classic CAN frame.
+pub const MAX_DATA: usize = 8;
+
+/// Errors from sending or receiving CAN frames.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Error {
+    /// The data to send is longer than [`MAX_DATA`] bytes.
+    TooLong,
+    /// The TWAI controller reported an error.
+    Twai(EspTwaiError),
+}
+
+impl From<EspTwaiError> for Error {
+    fn from(e: EspTwaiError) -> Self {
+        Self::Twai(e)
+    }
+}
+
+/// Configures the TWAI controller for a transceiver with its RX pin connected to _GPIO2_ and its
+/// TX pin connected to _GPIO9_. TX is recessive (high) when idle and keeps the strapping pin
+/// _GPIO9_ in the state required for booting.
+///
+/// Set up filters on the returned configuration, for example with [`accept_only`], and start it
+/// with [`Can::new`]. Without filters, all frames get received.
+pub fn configure<'a>(
+    twai0: TWAI0<'a>,
+    rx: GPIO2<'a>,
+    tx: GPIO9<'a>,
+    baud_rate: BaudRate,
+) -> TwaiConfiguration<'a, Async> {
+    TwaiConfiguration::new(twai0, rx, tx, baud_rate, TwaiMode::Normal).into_async()
+}
+
+/// Returns a filter accepting only standard data frames with the given `id`.
+pub fn accept_only(id: StandardId) -> SingleStandardFilter {
+    SingleStandardFilter::new_from_code_mask(id, StandardId::MAX, false, true, [0; 2], [0; 2])
+}
+
+/// A running TWAI controller sending and receiving frames asynchronously.
+pub struct Can<'a> {
+    twai: Twai<'a, Async>,
+}
+
+impl<'a> Can<'a> {
+    /// Starts the controller with `config` from [`configure`].
+    pub fn new(config: TwaiConfiguration<'a, Async>) -> Self {
+        Self {
+            twai: config.start(),
+        }
+    }
+
+    /// Returns the underlying esp-hal driver for using functionality not covered here.
+    pub fn twai_mut(&mut self) -> &mut Twai<'a, Async> {
+        &mut self.twai
+    }
+
+    /// Stops the controller and returns its configuration, for example for changing the filter.
+    pub fn free(self) -> TwaiConfiguration<'a, Async> {
+        self.twai.stop()
+    }
+
+    /// Sends `data` of up to [`MAX_DATA`] bytes as a data frame with `id`. Waits until the frame
+    /// has been sent.
+    pub async fn send(&mut self, id: impl Into<Id>, data: &[u8]) -> Result<(), Error> {
+        let frame = EspTwaiFrame::new(id, data).ok_or(Error::TooLong)?;
+        self.send_frame(&frame).await
+    }
+
+    /// Sends `frame`, for example a remote frame. Waits until the frame has been sent.
+    pub async fn send_frame(&mut self, frame: &EspTwaiFrame) -> Result<(), Error> {
+        log::trace!("CAN: sending {:?}", frame);
+        Ok(self.twai.transmit_async(frame).await?)
+    }
+
+    /// Waits for the next frame passing the filter.
+    pub async fn receive(&mut self) -> Result<EspTwaiFrame, Error> {
+        let frame = self.twai.receive_async().await?;
+        log::trace!("CAN: received {:?}", frame);
+        Ok(frame)
+    }
+}
@@ -0,0 +1,168 @@
+//! Support for the SHT3x humidity and temperature sensors on the I2C bus.
+//!
+//! The SHT30, SHT31 and SHT35 breakout boards connect to the bus from [`crate::i2c::open`]. Most
+//! of them have their address pin pulled low for [`Sht3x::ADDRESS`].
+//!
+//! ```rust
+//! let bus = i2c::open(board.peripherals.i2c0, board.peripherals.gpio2, board.peripherals.gpio9)?;
+//! let mut sht = Sht3x::new(bus);
+//!
+//! let climate = sht.measure().await?;
+//! log::info!("{} °C, {} %", climate.temperature, climate.humidity);
+//! ```
+//!
+//! A [`Sht3x`] is a [`Sensor`] for the temperature and can be sampled by a
+//! [`crate::sensor::Poller`]. [`Sht3x::humidity`] provides the humidity as a sensor of its own:
+//!
+//! ```rust
+//! sensor::poll(&mut sht).await;
+//! sensor::poll(&mut sht.humidity()).await;
+//! ```
+
+use embassy_time::{Duration, Timer};
+use embedded_hal_async::i2c::I2c;
+
+use crate::sensor::{Sensor, Value};
+
+/// Errors from talking to a SHT3x.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Error<E> {
+    /// Talking to the sensor over the bus failed.
+    Bus(E),
+    /// The data got corrupted on the way.
+    Crc,
+}
+
+/// A measurement of a SHT3x.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Climate {
+    /// The temperature in degrees Celsius.
+    pub temperature: f32,
+    /// The relative humidity in percent.
+    pub humidity: f32,
+}
+
+/// Computes the Sensirion CRC-8 (polynomial x^8 + x^5 + x^4 + 1, initial value 0xff) over `data`.
+fn crc8(data: &[u8]) -> u8 {
+    data.iter().fold(0xff, |crc, byte| {
+        (0..8).fold(crc ^ byte, |crc, _| match crc & 0x80 {
+            0 => crc << 1,
+            _ => (crc << 1) ^ 0x31,
+        })
+    })
+}
+
+/// Returns the big-endian word from the first two bytes of `data` if the CRC in its third byte
+/// matches.
+fn checked_word<E>(data: &[u8]) -> Result<u16, Error<E>> {
+    match data {
+        [msb, lsb, crc] if crc8(&[*msb, *lsb]) == *crc => Ok(u16::from_be_bytes([*msb, *lsb])),
+        _ => Err(Error::Crc),
+    }
+}
+
+/// A SHT3x humidity and temperature sensor attached to an I2C bus.
+#[derive(Debug)]
+pub struct Sht3x<B> {
+    bus: B,
+    address: u8,
+}
+
+impl<B: I2c> Sht3x<B> {
+    /// The default I2C address with the address pin pulled low.
+    pub const ADDRESS: u8 = 0x44;
+    /// The I2C address with the address pin pulled high.
+    pub const ALTERNATE_ADDRESS: u8 = 0x45;
+
+    /// Starts a single measurement with high repeatability and without clock stretching.
+    const MEASURE: [u8; 2] = [0x24, 0x00];
+    /// The maximum time for a measurement with high repeatability.
+    const MEASUREMENT: Duration = Duration::from_millis(16);
+
+    /// Creates a new `Sht3x` on `bus` at [`Sht3x::ADDRESS`].
+    pub fn new(bus: B) -> Self {
+        Self {
+            bus,
+            address: Self::ADDRESS,
+        }
+    }
+
+    /// Talks to the sensor at `address`, for example [`Sht3x::ALTERNATE_ADDRESS`].
+    pub fn with_address(self, address: u8) -> Self {
+        Self { address, ..self }
+    }
+
+    /// Returns the underlying I2C bus.
+    pub fn free(self) -> B {
+        self.bus
+    }
+
+    /// Returns the relative humidity as [`Sensor`]. Sampling it takes a measurement of its own.
+    pub fn humidity(&mut self) -> Humidity<'_, B> {
+        Humidity { sht: self }
+    }
+
+    /// Measures the temperature and the relative humidity. This takes about 16 ms.
+    pub async fn measure(&mut self) -> Result<Climate, Error<B::Error>> {
+        self.bus
+            .write(self.address, &Self::MEASURE)
+            .await
+            .map_err(Error::Bus)?;
+        Timer::after(Self::MEASUREMENT).await;
+
+        // The temperature and the humidity, each followed by its CRC.
+        let mut data = [0; 6];
+        self.bus
+            .read(self.address, &mut data)
+            .await
+            .map_err(Error::Bus)?;
+        let temperature = f32::from(checked_word(&data[..3])?);
+        let humidity = f32::from(checked_word(&data[3..])?);
+
+        Ok(Climate {
+            temperature: -45.0 + 175.0 * temperature / 65535.0,
+            humidity: (100.0 * humidity / 65535.0).clamp(0.0, 100.0),
+        })
+    }
+
+    /// Measures for a [`Sensor`] sample and logs the error if this fails.
+    async fn sample_climate(&mut self) -> Option<Climate> {
+        self.measure()
+            .await
+            .inspect_err(|e| log::debug!("SHT3x: {:?}", e))
+            .ok()
+    }
+}
+
+impl<B: I2c> Sensor for Sht3x<B> {
+    fn name(&self) -> &'static str {
+        "sht3x"
+    }
+
+    /// Measures the temperature. See [`Sht3x::humidity`] for sampling the humidity.
+    async fn sample(&mut self) -> Option<Value> {
+        self.sample_climate()
+            .await
+            .map(|climate| Value::Temperature(climate.temperature))
+    }
+}
+
+/// The relative humidity from a [`Sht3x`] as [`Sensor`] of its own. See [`Sht3x::humidity`].
+#[derive(Debug)]
+pub struct Humidity<'a, B> {
+    sht: &'a mut Sht3x<B>,
+}
+
+impl<B: I2c> Sensor for Humidity<'_, B> {
+    fn name(&self) -> &'static str {
+        self.sht.name()
+    }
+
+    /// Measures the relative humidity.
+    async fn sample(&mut self) -> Option<Value> {
+        self.sht
+            .sample_climate()
+            .await
+            .map(|climate| Value::Humidity(climate.humidity))
+    }
+}
@@ -0,0 +1,62 @@
+//! Build-time configuration for tuning the memory footprint of the firmware.
+//!
+//! The values can be overridden by setting the respective environment variable when building,
+//! either on the command line or in the `[env]` section of `.cargo/config.toml`:
+//!
+//! ```text
+//! $ HAKKAA_HEAP_SIZE=32768 cargo build --release
+//! ```
+
+/// The size of the global heap in bytes set up by [`crate::board::Board::init`] when the `alloc`
+/// feature is enabled. Override with `HAKKAA_HEAP_SIZE`. Defaults to 64 KiB.
+pub const HEAP_SIZE: usize = match option_env!("HAKKAA_HEAP_SIZE") {
+    Some(size) => parse_u64(size) as usize,
+    None => 64 * 1024,
+};
+
+/// The maximum number of tasks subscribed to [`crate::system`] events at the same time. Override
+/// with `HAKKAA_SYSTEM_SUBSCRIBERS`. Defaults to 4.
+pub const SYSTEM_SUBSCRIBERS: usize = match option_env!("HAKKAA_SYSTEM_SUBSCRIBERS") {
+    Some(count) => parse_u64(count) as usize,
+    None => 4,
+};
+
+/// The maximum number of tasks subscribed to [`crate::sensor`] readings at the same time. Override
+/// with `HAKKAA_SENSOR_SUBSCRIBERS`. Defaults to 4.
+pub const SENSOR_SUBSCRIBERS: usize = match option_env!("HAKKAA_SENSOR_SUBSCRIBERS") {
+    Some(count) => parse_u64(count) as usize,
+    None => 4,
+};
+
+/// The number of [`crate::sensor`] readings buffered for each subscriber. Override with
+/// `HAKKAA_SENSOR_CAPACITY`. Defaults to 8 and needs to be at least 1.
+pub const SENSOR_CAPACITY: usize = match option_env!("HAKKAA_SENSOR_CAPACITY") {
+    Some(count) => parse_u64(count) as usize,
+    None => 8,
+};
+
+const _: () = assert!(
+    SENSOR_CAPACITY > 0,
+    "HAKKAA_SENSOR_CAPACITY needs to be at least 1"
+);
+
+/// Parses a decimal number at compile time.
+///
+/// # Panics
+///
+/// Panics (at compile time when used in a constant) if `digits` is not a decimal number.
+pub(crate) const fn parse_u64(digits: &str) -> u64 {
+    let digits = digits.as_bytes();
+    let mut value = 0;
+    let mut index = 0;
+
+    assert!(!digits.is_empty(), "empty number");
+    while index < digits.len() {
+        let digit = digits[index];
+        assert!(digit.is_ascii_digit(), "not a decimal number");
+        value = value * 10 + (digit - b'0') as u64;
+        index += 1;
+    }
+
+    value
+}
@@ -0,0 +1,376 @@
+//! Support for 128x64 monochrome OLED displays with SSD1306 or SH1106 controller on the I2C bus.
+//!
+//! [`Oled`] renders into a frame buffer in RAM and [`Oled::flush`] transfers it to the display.
+//! Text is drawn with a built-in 5x7 font in a grid of [`COLUMNS`] by [`ROWS`] characters. On top
+//! of this, [`render_timer`] and [`render_menu`] draw the screens for a countdown timer and for
+//! picking an item from a list.
+//!
+//! ```rust
+//! let bus = i2c::open(board.peripherals.i2c0, board.peripherals.gpio2, board.peripherals.gpio9)?;
+//! let mut oled = Oled::new(bus, Controller::Ssd1306);
+//! oled.init().await?;
+//!
+//! oled.text(0, 0, "Hello Hakkaa!");
+//! oled.flush().await?;
+//! ```
+
+use embassy_time::Duration;
+use embedded_hal_async::i2c::I2c;
+
+/// The width of the display in pixels.
+pub const WIDTH: usize = 128;
+/// The height of the display in pixels.
+pub const HEIGHT: usize = 64;
+/// The number of text rows. Each row is a display page of eight pixels.
+pub const ROWS: usize = HEIGHT / 8;
+/// The number of text columns.
+pub const COLUMNS: usize = WIDTH / GLYPH_ADVANCE;
+
+/// The I2C address most displays use. Some can be switched to 0x3d.
+pub const DEFAULT_ADDRESS: u8 = 0x3c;
+
+/// The width of a glyph from the font.
+const GLYPH_WIDTH: usize = 5;
+/// The horizontal distance between glyphs, including one column of spacing.
+const GLYPH_ADVANCE: usize = GLYPH_WIDTH + 1;
+
+/// Control byte announcing a sequence of commands.
+const COMMANDS: u8 = 0x00;
+/// Control byte announcing a sequence of display data.
+const DATA: u8 = 0x40;
+
+/// The display controller. Both are largely compatible but differ in power setup and RAM layout.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Controller {
+    /// SSD1306 found on most 0.96" displays.
+    Ssd1306,
+    /// SH1106 found on most 1.3" displays. It has 132 columns of RAM with the visible ones
+    /// centered.
+    Sh1106,
+}
+
+impl Controller {
+    /// Returns the first RAM column which is visible on the display.
+    fn column_offset(self) -> u8 {
+        match self {
+            Self::Ssd1306 => 0,
+            Self::Sh1106 => 2,
+        }
+    }
+
+    /// Returns the commands for enabling the voltage supply of the display panel.
+    fn charge_pump(self) -> &'static [u8] {
+        match self {
+            // Enable the charge pump.
+            Self::Ssd1306 => &[0x8d, 0x14],
+            // Enable the DC-DC converter.
+            Self::Sh1106 => &[0xad, 0x8b],
+        }
+    }
+}
+
+/// A 128x64 OLED display attached to an I2C bus.
+#[derive(Debug)]
+pub struct Oled<B> {
+    bus: B,
+    address: u8,
+    controller: Controller,
+    /// The frame buffer organised in pages of eight pixel rows. Each byte is a column of a page
+    /// with the least significant bit at the top.
+    buffer: [[u8; WIDTH]; ROWS],
+}
+
+impl<B: I2c> Oled<B> {
+    /// Creates a new `Oled` at [`DEFAULT_ADDRESS`] on `bus`. Call [`Oled::init`] before showing
+    /// anything.
+    pub fn new(bus: B, controller: Controller) -> Self {
+        Self {
+            bus,
+            address: DEFAULT_ADDRESS,
+            controller,
+            buffer: [[0; WIDTH]; ROWS],
+        }
+    }
+
+    /// Sets the I2C address of the display.
+    pub fn with_address(self, address: u8) -> Self {
+        Self { address, ..self }
+    }
+
+    /// Returns the underlying I2C bus.
+    pub fn free(self) -> B {
+        self.bus
+    }
+
+    /// Initializes the display controller, clears the display, and switches it on.
+    pub async fn init(&mut self) -> Result<(), B::Error> {
+        // Display off, clock divider and oscillator, multiplex ratio for 64 lines, no display
+        // offset, and start line zero.
+        self.commands(&[0xae, 0xd5, 0x80, 0xa8, 0x3f, 0xd3, 0x00, 0x40])
+            .await?;
+        self.commands(self.controller.charge_pump()).await?;
+        // Mirrored segments and COM scan direction for the usual mounting, alternative COM pin
+        // configuration, contrast, pre-charge period, and VCOMH deselect level.
+        self.commands(&[0xa1, 0xc8, 0xda, 0x12, 0x81, 0xcf, 0xd9, 0xf1, 0xdb, 0x40])
+            .await?;
+
+        self.clear();
+        self.flush().await?;
+
+        // Show the RAM contents in normal (not inverted) mode and switch the display on.
+        self.commands(&[0xa4, 0xa6, 0xaf]).await
+    }
+
+    /// Clears the frame buffer.
+    pub fn clear(&mut self) {
+        self.buffer = [[0; WIDTH]; ROWS];
+    }
+
+    /// Clears `row` in the frame buffer.
+    pub fn clear_row(&mut self, row: usize) {
+        if let Some(page) = self.buffer.get_mut(row) {
+            *page = [0; WIDTH];
+        }
+    }
+
+    /// Draws `text` starting at `row` and `column` into the frame buffer. Text beyond the right
+    /// edge gets cut off and characters missing from the font are shown as `?`.
+    pub fn text(&mut self, row: usize, column: usize, text: &str) {
+        let Some(page) = self.buffer.get_mut(row) else {
+            return;
+        };
+
+        let start = column.saturating_mul(GLYPH_ADVANCE);
+        let cells = page.get_mut(start..).unwrap_or_default();
+        for (cell, c) in cells.chunks_mut(GLYPH_ADVANCE).zip(text.chars()) {
+            for (pixels, glyph) in cell.iter_mut().zip(glyph(c).iter().chain([&0])) {
+                *pixels = *glyph;
+            }
+        }
+    }
+
+    /// Draws a bar across `row` which is filled for the fraction `done` of `total`.
+    pub fn progress(&mut self, row: usize, done: u64, total: u64) {
+        let Some(page) = self.buffer.get_mut(row) else {
+            return;
+        };
+
+        let inner = WIDTH as u64 - 2;
+        let filled = match total {
+            0 => inner,
+            total => done.min(total) * inner / total,
+        };
+
+        for (x, pixels) in page.iter_mut().enumerate() {
+            let x = x as u64;
+            *pixels = match x {
+                // Left and right border.
+                0 => 0x7e,
+                x if x == inner + 1 => 0x7e,
+                // Filled part.
+                x if x <= filled => 0x7e,
+                // Top and bottom border of the empty part.
+                _ => 0x42,
+            };
+        }
+    }
+
+    /// Inverts `row` in the frame buffer, for example for highlighting a selected item.
+    pub fn invert_row(&mut self, row: usize) {
+        if let Some(page) = self.buffer.get_mut(row) {
+            page.iter_mut().for_each(|pixels| *pixels = !*pixels);
+        }
+    }
+
+    /// Transfers the frame buffer to the display.
+    pub async fn flush(&mut self) -> Result<(), B::Error> {
+        let offset = self.controller.column_offset();
+        let mut data = [DATA; WIDTH + 1];
+
+        for (index, page) in self.buffer.iter().enumerate() {
+            // Page start address and lower and higher nibble of the start column. This page
+            // addressing works the same for both controllers.
+            let commands = [
+                COMMANDS,
+                0xb0 | index as u8,
+                offset & 0x0f,
+                0x10 | (offset >> 4),
+            ];
+            self.bus.write(self.address, &commands).await?;
+
+            for (pixels, column) in data.iter_mut().skip(1).zip(page) {
+                *pixels = *column;
+            }
+            self.bus.write(self.address, &data).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Sends `commands` to the display controller.
+    async fn commands(&mut self, commands: &[u8]) -> Result<(), B::Error> {
+        for command in commands {
+            self.bus.write(self.address, &[COMMANDS, *command]).await?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Renders a countdown timer screen into the frame buffer of `oled`: `title` at the top, the
+/// `remaining` time as minutes and seconds, and a bar showing the progress through `total`.
+pub fn render_timer<B: I2c>(oled: &mut Oled<B>, title: &str, remaining: Duration, total: Duration) {
+    let seconds = remaining.as_secs();
+    let mut time = [b' '; 5];
+    for (digit, value) in time.iter_mut().zip([
+        seconds / 600 % 10,
+        seconds / 60 % 10,
+        10,
+        seconds % 60 / 10,
+        seconds % 10,
+    ]) {
+        *digit = match value {
+            10 => b':',
+            value => b'0' + value as u8,
+        };
+    }
+
+    oled.clear();
+    oled.text(0, 0, title);
+    // The buffer only contains ASCII digits and the separator.
+    oled.text(
+        3,
+        (COLUMNS - time.len()) / 2,
+        core::str::from_utf8(&time).unwrap_or("??:??"),
+    );
+    let elapsed = total.as_secs().saturating_sub(seconds);
+    oled.progress(6, elapsed, total.as_secs());
+}
+
+/// Renders a menu screen into the frame buffer of `oled`: `title` at the top and the `items`
+/// below with `selected` highlighted. Items beyond the bottom of the display are left out.
+pub fn render_menu<B: I2c>(oled: &mut Oled<B>, title: &str, items: &[&str], selected: usize) {
+    oled.clear();
+    oled.text(0, 0, title);
+
+    for (index, item) in items.iter().enumerate().take(ROWS - 2) {
+        let row = index + 2;
+        let marker = if index == selected { ">" } else { " " };
+        oled.text(row, 0, marker);
+        oled.text(row, 2, item);
+        if index == selected {
+            oled.invert_row(row);
+        }
+    }
+}
+
+/// Returns the glyph for `c` or the one for `?` if the font doesn't contain it.
+fn glyph(c: char) -> &'static [u8; GLYPH_WIDTH] {
+    let index = (c as usize).wrapping_sub(FIRST_GLYPH as usize);
+    FONT.get(index)
+        .or(FONT.get(('?' as usize) - FIRST_GLYPH as usize))
+        .unwrap_or(&[0; GLYPH_WIDTH])
+}
+
+/// The first character contained in [`FONT`].
+const FIRST_GLYPH: char = ' ';
+
+/// A 5x7 font for the printable ASCII characters. Each glyph is stored as five columns with the
+/// least significant bit at the top.
+static FONT: [[u8; GLYPH_WIDTH]; 95] = [
+    [0x00, 0x00, 0x00, 0x00, 0x00], // ' '
+    [0x00, 0x00, 0x5f, 0x00, 0x00], // '!'
+    [0x00, 0x07, 0x00, 0x07, 0x00], // '"'
+    [0x14, 0x7f, 0x14, 0x7f, 0x14], // '#'
+    [0x24, 0x2a, 0x7f, 0x2a, 0x12], // '$'
+    [0x23, 0x13, 0x08, 0x64, 0x62], // '%'
+    [0x36, 0x49, 0x55, 0x22, 0x50], // '&'
+    [0x00, 0x05, 0x03, 0x00, 0x00], // '''
+    [0x00, 0x1c, 0x22, 0x41, 0x00], // '('
+    [0x00, 0x41, 0x22, 0x1c, 0x00], // ')'
+    [0x08, 0x2a, 0x1c, 0x2a, 0x08], // '*'
+    [0x08, 0x08, 0x3e, 0x08, 0x08], // '+'
+    [0x00, 0x50, 0x30, 0x00, 0x00], // ','
+    [0x08, 0x08, 0x08, 0x08, 0x08], // '-'
+    [0x00, 0x60, 0x60, 0x00, 0x00], // '.'
+    [0x20, 0x10, 0x08, 0x04, 0x02], // '/'
+    [0x3e, 0x51, 0x49, 0x45, 0x3e], // '0'
+    [0x00, 0x42, 0x7f, 0x40, 0x00], // '1'
+    [0x42, 0x61, 0x51, 0x49, 0x46], // '2'
+    [0x21, 0x41, 0x45, 0x4b, 0x31], // '3'
+    [0x18, 0x14, 0x12, 0x7f, 0x10], // '4'
+    [0x27, 0x45, 0x45, 0x45, 0x39], // '5'
+    [0x3c, 0x4a, 0x49, 0x49, 0x30], // '6'
+    [0x01, 0x71, 0x09, 0x05, 0x03], // '7'
+    [0x36, 0x49, 0x49, 0x49, 0x36], // '8'
+    [0x06, 0x49, 0x49, 0x29, 0x1e], // '9'
+    [0x00, 0x36, 0x36, 0x00, 0x00], // ':'
+    [0x00, 0x56, 0x36, 0x00, 0x00], // ';'
+    [0x08, 0x14, 0x22, 0x41, 0x00], // '<'
+    [0x14, 0x14, 0x14, 0x14, 0x14], // '='
+    [0x00, 0x41, 0x22, 0x14, 0x08], // '>'
+    [0x02, 0x01, 0x51, 0x09, 0x06], // '?'
+    [0x32, 0x49, 0x79, 0x41, 0x3e], // '@'
+    [0x7e, 0x11, 0x11, 0x11, 0x7e], // 'A'
+    [0x7f, 0x49, 0x49, 0x49, 0x36], // 'B'
+    [0x3e, 0x41, 0x41, 0x41, 0x22], // 'C'
+    [0x7f, 0x41, 0x41, 0x22, 0x1c], // 'D'
+    [0x7f, 0x49, 0x49, 0x49, 0x41], // 'E'
+    [0x7f, 0x09, 0x09, 0x09, 0x01], // 'F'
+    [0x3e, 0x41, 0x49, 0x49, 0x7a], // 'G'
+    [0x7f, 0x08, 0x08, 0x08, 0x7f], // 'H'
+    [0x00, 0x41, 0x7f, 0x41, 0x00], // 'I'
+    [0x20, 0x40, 0x41, 0x3f, 0x01], // 'J'
+    [0x7f, 0x08, 0x14, 0x22, 0x41], // 'K'
+    [0x7f, 0x40, 0x40, 0x40, 0x40], // 'L'
+    [0x7f, 0x02, 0x0c, 0x02, 0x7f], // 'M'
+    [0x7f, 0x04, 0x08, 0x10, 0x7f], // 'N'
+    [0x3e, 0x41, 0x41, 0x41, 0x3e], // 'O'
+    [0x7f, 0x09, 0x09, 0x09, 0x06], // 'P'
+    [0x3e, 0x41, 0x51, 0x21, 0x5e], // 'Q'
+    [0x7f, 0x09, 0x19, 0x29, 0x46], // 'R'
+    [0x46, 0x49, 0x49, 0x49, 0x31], // 'S'
+    [0x01, 0x01, 0x7f, 0x01, 0x01], // 'T'
+    [0x3f, 0x40, 0x40, 0x40, 0x3f], // 'U'
+    [0x1f, 0x20, 0x40, 0x20, 0x1f], // 'V'
+    [0x3f, 0x40, 0x38, 0x40, 0x3f], // 'W'
+    [0x63, 0x14, 0x08, 0x14, 0x63], // 'X'
+    [0x07, 0x08, 0x70, 0x08, 0x07], // 'Y'
+    [0x61, 0x51, 0x49, 0x45, 0x43], // 'Z'
+    [0x00, 0x7f, 0x41, 0x41, 0x00], // '['
+    [0x02, 0x04, 0x08, 0x10, 0x20], // '\'
+    [0x00, 0x41, 0x41, 0x7f, 0x00], // ']'
+    [0x04, 0x02, 0x01, 0x02, 0x04], // '^'
+    [0x40, 0x40, 0x40, 0x40, 0x40], // '_'
+    [0x00, 0x01, 0x02, 0x04, 0x00], // '`'
+    [0x20, 0x54, 0x54, 0x54, 0x78], // 'a'
+    [0x7f, 0x48, 0x44, 0x44, 0x38], // 'b'
+    [0x38, 0x44, 0x44, 0x44, 0x20], // 'c'
+    [0x38, 0x44, 0x44, 0x48, 0x7f], // 'd'
+    [0x38, 0x54, 0x54, 0x54, 0x18], // 'e'
+    [0x08, 0x7e, 0x09, 0x01, 0x02], // 'f'
+    [0x0c, 0x52, 0x52, 0x52, 0x3e], // 'g'
+    [0x7f, 0x08, 0x04, 0x04, 0x78], // 'h'
+    [0x00, 0x44, 0x7d, 0x40, 0x00], // 'i'
+    [0x20, 0x40, 0x44, 0x3d, 0x00], // 'j'
+    [0x7f, 0x10, 0x28, 0x44, 0x00], // 'k'
+    [0x00, 0x41, 0x7f, 0x40, 0x00], // 'l'
+    [0x7c, 0x04, 0x18, 0x04, 0x78], // 'm'
+    [0x7c, 0x08, 0x04, 0x04, 0x78], // 'n'
+    [0x38, 0x44, 0x44, 0x44, 0x38], // 'o'
+    [0x7c, 0x14, 0x14, 0x14, 0x08], // 'p'
+    [0x08, 0x14, 0x14, 0x18, 0x7c], // 'q'
+    [0x7c, 0x08, 0x04, 0x04, 0x08], // 'r'
+    [0x48, 0x54, 0x54, 0x54, 0x20], // 's'
+    [0x04, 0x3f, 0x44, 0x40, 0x20], // 't'
+    [0x3c, 0x40, 0x40, 0x20, 0x7c], // 'u'
+    [0x1c, 0x20, 0x40, 0x20, 0x1c], // 'v'
+    [0x3c, 0x40, 0x30, 0x40, 0x3c], // 'w'
+    [0x44, 0x28, 0x10, 0x28, 0x44], // 'x'
+    [0x0c, 0x50, 0x50, 0x50, 0x3c], // 'y'
+    [0x44, 0x64, 0x54, 0x4c, 0x44], // 'z'
+    [0x00, 0x08, 0x36, 0x41, 0x00], // '{'
+    [0x00, 0x00, 0x7f, 0x00, 0x00], // '|'
+    [0x00, 0x41, 0x36, 0x08, 0x00], // '}'
+    [0x08, 0x04, 0x08, 0x10, 0x08], // '~'
+];
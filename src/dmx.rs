@@ -0,0 +1,116 @@
+//! A DMX512 transmitter for driving stage lighting fixtures from the secondary UART.
+//!
+//! DMX512 needs an RS-485 transceiver like the MAX485 with its driver permanently enabled. Its
+//! input gets connected to _GPIO9_ which is idle high like the DMX line between packets and thus
+//! keeps the strapping pin in the state required for booting.
+
+use esp_hal::peripherals::{GPIO9, UART1};
+use esp_hal::uart::{Config, ConfigError, StopBits, TxError, UartTx};
+use esp_hal::Async;
+
+use crate::led::STOREY_LEDS;
+
+/// The number of channels (slots) in a DMX512 universe.
+pub const CHANNELS: usize = 512;
+
+/// The DMX512 line rate in bits per second.
+const BAUDRATE: u32 = 250_000;
+/// The rate for sending the break. A zero byte at this rate holds the line low for 90 µs as a
+/// break (at least 88 µs). Its two stop bits follow as a mark after break of 20 µs (at least 8 µs).
+const BREAK_BAUDRATE: u32 = 100_000;
+/// The start code for dimmer data.
+const NULL_START_CODE: u8 = 0x00;
+
+/// Errors from sending a DMX512 packet.
+#[derive(Debug)]
+pub enum Error {
+    /// Switching between break and data rate failed.
+    Config(ConfigError),
+    /// Transmitting data failed.
+    Transmit(TxError),
+}
+
+impl From<ConfigError> for Error {
+    fn from(e: ConfigError) -> Self {
+        Self::Config(e)
+    }
+}
+
+impl From<TxError> for Error {
+    fn from(e: TxError) -> Self {
+        Self::Transmit(e)
+    }
+}
+
+/// A DMX512 transmitter holding a full universe of channel values.
+pub struct DmxOutput<'a> {
+    uart: UartTx<'a, Async>,
+    /// The start code followed by the channel values.
+    packet: [u8; CHANNELS + 1],
+}
+
+impl<'a> DmxOutput<'a> {
+    /// Creates a new `DmxOutput` sending on _GPIO9_ with all channels set to zero.
+    pub fn new(uart1: UART1<'a>, tx: GPIO9<'a>) -> Result<Self, ConfigError> {
+        let uart = UartTx::new(uart1, Self::config(BAUDRATE))?
+            .with_tx(tx)
+            .into_async();
+
+        let mut packet = [0; CHANNELS + 1];
+        packet[0] = NULL_START_CODE;
+
+        Ok(Self { uart, packet })
+    }
+
+    /// Returns the channel values. Mind that DMX512 counts channels from one, so channel _n_ is at
+    /// index _n - 1_.
+    pub fn channels(&self) -> &[u8] {
+        &self.packet[1..]
+    }
+
+    /// Returns the channel values for modification. See [`DmxOutput::channels`] for indexing.
+    pub fn channels_mut(&mut self) -> &mut [u8] {
+        &mut self.packet[1..]
+    }
+
+    /// Maps a storey LED `pattern` like the one for [`crate::led::Storeys::set_pattern`] to the
+    /// eight channels starting at `first` (counting from one). Lit storeys set their channel to
+    /// full intensity. Channels beyond the universe are ignored.
+    pub fn set_storeys(&mut self, first: usize, pattern: u8) {
+        let channels = self.channels_mut().iter_mut().skip(first.saturating_sub(1));
+
+        for (index, channel) in channels.take(STOREY_LEDS).enumerate() {
+            *channel = match pattern & (1 << index) {
+                0 => 0,
+                _ => u8::MAX,
+            };
+        }
+    }
+
+    /// Sends the universe as a single packet including break and mark after break. Sending a full
+    /// universe takes about 23 ms.
+    pub async fn send(&mut self) -> Result<(), Error> {
+        self.uart.apply_config(&Self::config(BREAK_BAUDRATE))?;
+        Self::write_all(&mut self.uart, &[0]).await?;
+        self.uart.apply_config(&Self::config(BAUDRATE))?;
+        Self::write_all(&mut self.uart, &self.packet).await?;
+
+        Ok(())
+    }
+
+    /// Returns the UART configuration for DMX512 at the given `baudrate`.
+    fn config(baudrate: u32) -> Config {
+        Config::default()
+            .with_baudrate(baudrate)
+            .with_stop_bits(StopBits::_2)
+    }
+
+    /// Writes all of `bytes` and waits until they have been sent.
+    async fn write_all(uart: &mut UartTx<'a, Async>, mut bytes: &[u8]) -> Result<(), TxError> {
+        while !bytes.is_empty() {
+            let written = uart.write_async(bytes).await?;
+            bytes = bytes.get(written..).unwrap_or_default();
+        }
+        uart.flush_async().await
+    }
+}
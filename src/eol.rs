@@ -0,0 +1,115 @@
+//! Long-running checks for qualifying boards and firmware releases.
+//!
+//! [`soak`] keeps the board busy for hours, for example overnight, while watching for memory leaks
+//! and overheating. It cycles the storey LEDs, samples the chip temperature through
+//! [`crate::sensor::poll`] and buffers the samples on the heap. The vitals get logged every
+//! [`LOG_INTERVAL`].
+//!
+//! ```rust
+//! let mut storeys = Storeys::new(board.storey_leds);
+//! let mut chip = ChipTemperature::new(board.peripherals.tsens)?;
+//!
+//! if eol::soak(&mut storeys, &mut chip, 8).await {
+//!     storeys.all_on();
+//! }
+//! ```
+//!
+//! This module is only available with the `alloc` feature.
+
+use alloc::vec::Vec;
+use core::convert::Infallible;
+
+use embassy_futures::select::{select, Either};
+use embassy_time::{Duration, Instant};
+
+use crate::led::{Period, Storeys, Ticker};
+use crate::sensor::{self, ChipTemperature, Value};
+
+/// The interval for logging the board's vitals and checking them against their limits.
+pub const LOG_INTERVAL: Duration = Duration::from_secs(60);
+/// The period for sampling the chip temperature.
+pub const SAMPLE_PERIOD: Period = Period::from_secs(1);
+/// The step for cycling the storey LEDs.
+pub const CYCLE_STEP: Period = Period::from_millis(100);
+/// The heap usage growth in bytes above which a leak is reported.
+pub const MAX_HEAP_GROWTH: usize = 1024;
+/// The chip temperature in degrees Celsius above which overheating is reported.
+pub const MAX_TEMPERATURE: f32 = 70.0;
+
+/// Runs the soak test for `hours` and returns whether the board stayed within its limits.
+///
+/// Problems get logged as warnings as soon as they show up, but the test keeps running until the
+/// end. The storey LEDs are left in an undefined state.
+pub async fn soak(storeys: &mut Storeys<'_>, chip: &mut ChipTemperature<'_>, hours: u64) -> bool {
+    let duration = Duration::from_secs(hours.saturating_mul(60 * 60));
+    log::info!("soak test for {} hours", hours);
+
+    match select(exercise(storeys), monitor(chip, duration)).await {
+        Either::First(never) => match never {},
+        Either::Second(passed) => passed,
+    }
+}
+
+/// Keeps cycling the storey LEDs.
+async fn exercise(storeys: &mut Storeys<'_>) -> Infallible {
+    loop {
+        storeys.cycle(CYCLE_STEP).await;
+    }
+}
+
+/// Samples the chip temperature and periodically logs and checks the vitals for `duration`.
+async fn monitor(chip: &mut ChipTemperature<'_>, duration: Duration) -> bool {
+    let end = Instant::now() + duration;
+    let heap_baseline = esp_alloc::HEAP.used();
+    let mut next_log = Instant::now() + LOG_INTERVAL;
+    let mut ticker = Ticker::every(SAMPLE_PERIOD);
+    let mut samples = Vec::new();
+    let mut passed = true;
+
+    while Instant::now() < end {
+        ticker.next().await;
+
+        if let Some(reading) = sensor::poll(chip).await {
+            if let Value::Temperature(celsius) = reading.value {
+                samples.push(celsius);
+            }
+        }
+
+        if Instant::now() < next_log {
+            continue;
+        }
+        next_log += LOG_INTERVAL;
+
+        // Free the samples of this interval before looking at the heap. Whatever remains
+        // allocated is a leak.
+        let interval = core::mem::take(&mut samples);
+        let temperature = interval.iter().copied().fold(f32::MIN, f32::max);
+        let count = interval.len();
+        drop(interval);
+
+        let heap_used = esp_alloc::HEAP.used();
+        log::info!(
+            "uptime: {} s, heap used: {} B, heap free: {} B, max temperature: {} °C ({} samples)",
+            Instant::now().as_secs(),
+            heap_used,
+            esp_alloc::HEAP.free(),
+            temperature,
+            count
+        );
+
+        if heap_used > heap_baseline + MAX_HEAP_GROWTH {
+            log::warn!("heap usage grew by {} B", heap_used - heap_baseline);
+            passed = false;
+        }
+        if temperature > MAX_TEMPERATURE {
+            log::warn!("chip temperature is above {} °C", MAX_TEMPERATURE);
+            passed = false;
+        }
+        if count == 0 {
+            log::warn!("no chip temperature samples");
+            passed = false;
+        }
+    }
+
+    passed
+}
@@ -0,0 +1,174 @@
+//! Drawing on the storey LEDs with [embedded-graphics](https://docs.rs/embedded-graphics).
+//!
+//! The storey LEDs form a display of a single column with [`STOREY_LEDS`] monochrome pixels.
+//! Row zero is the topmost storey _D8_ and row seven the bottommost storey _D1_, so a pixel
+//! `(0, y)` maps to the bit `7 - y` of a pattern for [`Storeys::set_pattern`].
+//!
+//! [`StoreyDisplay`] draws directly to the storey LEDs:
+//!
+//! ```rust
+//! let mut display = StoreyDisplay::new(storeys);
+//!
+//! Line::new(Point::new(0, 2), Point::new(0, 5))
+//!     .into_styled(PrimitiveStyle::with_stroke(BinaryColor::On, 1))
+//!     .draw(&mut display)?;
+//! ```
+//!
+//! A [`Frame`] is a frame buffer of any size. Wider frames hold text or images to be shown on the
+//! storey LEDs column by column, for example while waving the board in persistence of vision
+//! mode:
+//!
+//! ```rust
+//! let mut frame = Frame::<32, STOREY_LEDS>::new();
+//! Text::new("Hi!", Point::new(0, 6), MonoTextStyle::new(&FONT_5X7, BinaryColor::On))
+//!     .draw(&mut frame)?;
+//!
+//! for x in 0..frame.size().width as usize {
+//!     storeys.set_pattern(frame.column(x));
+//!     Timer::after(Duration::from_millis(2)).await;
+//! }
+//! ```
+
+use crate::led::{Storeys, STOREY_LEDS};
+use core::convert::Infallible;
+use embedded_graphics_core::{
+    pixelcolor::BinaryColor,
+    prelude::{DrawTarget, OriginDimensions, Size},
+    Pixel,
+};
+
+/// A monochrome frame buffer with `W` columns and `H` rows for drawing with embedded-graphics.
+///
+/// Pixels outside of the frame are silently clipped.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Frame<const W: usize, const H: usize> {
+    rows: [[bool; W]; H],
+}
+
+impl<const W: usize, const H: usize> Frame<W, H> {
+    /// Creates a new frame with all pixels off.
+    pub const fn new() -> Self {
+        Self {
+            rows: [[false; W]; H],
+        }
+    }
+
+    /// Returns the color of the pixel at `x` and `y` or `None` if it is outside of the frame.
+    pub fn pixel(&self, x: usize, y: usize) -> Option<BinaryColor> {
+        self.rows
+            .get(y)
+            .and_then(|row| row.get(x))
+            .map(|&on| BinaryColor::from(on))
+    }
+
+    /// Returns the column `x` as pattern for [`Storeys::set_pattern`].
+    ///
+    /// The bottom row maps to bit zero and the row above to bit one. Only the bottom
+    /// [`STOREY_LEDS`] rows are taken into account. A column outside of the frame results in an
+    /// empty pattern.
+    pub fn column(&self, x: usize) -> u8 {
+        self.rows
+            .iter()
+            .rev()
+            .take(STOREY_LEDS)
+            .enumerate()
+            .filter(|(_, row)| row.get(x).copied().unwrap_or(false))
+            .fold(0, |pattern, (bit, _)| pattern | 1 << bit)
+    }
+}
+
+impl<const W: usize, const H: usize> Default for Frame<W, H> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const W: usize, const H: usize> OriginDimensions for Frame<W, H> {
+    fn size(&self) -> Size {
+        Size::new(W as u32, H as u32)
+    }
+}
+
+impl<const W: usize, const H: usize> DrawTarget for Frame<W, H> {
+    type Color = BinaryColor;
+    type Error = Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        for Pixel(point, color) in pixels {
+            let (Ok(x), Ok(y)) = (usize::try_from(point.x), usize::try_from(point.y)) else {
+                continue;
+            };
+
+            if let Some(pixel) = self.rows.get_mut(y).and_then(|row| row.get_mut(x)) {
+                *pixel = color.is_on();
+            }
+        }
+
+        Ok(())
+    }
+
+    fn clear(&mut self, color: Self::Color) -> Result<(), Self::Error> {
+        self.rows = [[color.is_on(); W]; H];
+        Ok(())
+    }
+}
+
+/// A single column display with one pixel per storey LED.
+///
+/// Every drawing operation is shown on the storey LEDs right away.
+#[derive(Debug)]
+pub struct StoreyDisplay<'a> {
+    storeys: Storeys<'a>,
+    frame: Frame<1, STOREY_LEDS>,
+}
+
+impl<'a> StoreyDisplay<'a> {
+    /// Creates a new display from the storey LEDs and switches them all off.
+    pub fn new(mut storeys: Storeys<'a>) -> Self {
+        storeys.all_off();
+
+        Self {
+            storeys,
+            frame: Frame::new(),
+        }
+    }
+
+    /// Returns the storey LEDs for using them otherwise.
+    pub fn free(self) -> Storeys<'a> {
+        self.storeys
+    }
+
+    /// Returns the frame currently shown.
+    pub fn frame(&self) -> &Frame<1, STOREY_LEDS> {
+        &self.frame
+    }
+}
+
+impl OriginDimensions for StoreyDisplay<'_> {
+    fn size(&self) -> Size {
+        self.frame.size()
+    }
+}
+
+impl DrawTarget for StoreyDisplay<'_> {
+    type Color = BinaryColor;
+    type Error = Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        self.frame.draw_iter(pixels)?;
+        self.storeys.set_pattern(self.frame.column(0));
+        Ok(())
+    }
+
+    fn clear(&mut self, color: Self::Color) -> Result<(), Self::Error> {
+        self.frame.clear(color)?;
+        self.storeys.set_pattern(self.frame.column(0));
+        Ok(())
+    }
+}
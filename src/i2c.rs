@@ -0,0 +1,85 @@
+//! Helpers for working with devices attached to an I2C bus.
+//!
+//! The board has no dedicated I2C header, but the spare pins _GPIO2_ (SDA) and _GPIO9_ (SCL) make
+//! up the bus set up by [`open`]. Most breakout boards already come with pull-up resistors, which
+//! also keep the strapping pin _GPIO9_ high during reset.
+
+use embedded_hal_async::i2c::I2c;
+use esp_hal::i2c::master::{self, ConfigError};
+use esp_hal::peripherals::{GPIO2, GPIO9, I2C0};
+use esp_hal::Async;
+use heapless::Vec;
+
+/// The lowest 7-bit address probed by [`scan`]. Addresses below are reserved by the I2C
+/// specification.
+pub const FIRST_ADDRESS: u8 = 0x08;
+/// The highest 7-bit address probed by [`scan`]. Addresses above are reserved by the I2C
+/// specification.
+pub const LAST_ADDRESS: u8 = 0x77;
+/// The number of addresses probed by [`scan`] and thus the maximum number of responders.
+pub const ADDRESSES: usize = (LAST_ADDRESS - FIRST_ADDRESS + 1) as usize;
+
+/// Well-known devices by their (default) address. Several devices share the same address, so this
+/// can only give a hint.
+static KNOWN_DEVICES: [(u8, &str); 14] = [
+    (0x20, "PCF8574/MCP23017 I/O expander"),
+    (0x23, "BH1750 light sensor"),
+    (0x27, "PCF8574 LCD backpack"),
+    (0x29, "VL53L0X distance sensor"),
+    (0x3c, "SSD1306/SH1106 OLED"),
+    (0x3d, "SSD1306/SH1106 OLED"),
+    (0x40, "INA219 power monitor/HTU21D humidity sensor"),
+    (0x44, "SHT3x humidity sensor"),
+    (0x45, "SHT3x humidity sensor"),
+    (0x48, "ADS1115 ADC/TMP102 temperature sensor"),
+    (0x57, "AT24C32 EEPROM"),
+    (0x68, "DS3231 RTC/MPU6050 IMU"),
+    (0x76, "BME280/BMP280 environmental sensor"),
+    (0x77, "BME280/BMP280 environmental sensor"),
+];
+
+/// Sets up the I2C controller with the default bus clock of 100 kHz for devices connected with SDA
+/// to _GPIO2_ and SCL to _GPIO9_.
+pub fn open<'a>(
+    i2c0: I2C0<'a>,
+    sda: GPIO2<'a>,
+    scl: GPIO9<'a>,
+) -> Result<master::I2c<'a, Async>, ConfigError> {
+    let bus = master::I2c::new(i2c0, master::Config::default())?
+        .with_sda(sda)
+        .with_scl(scl)
+        .into_async();
+
+    Ok(bus)
+}
+
+/// Returns a hint which device might be responding at `address`.
+pub fn hint(address: u8) -> Option<&'static str> {
+    KNOWN_DEVICES
+        .iter()
+        .find(|(known, _)| *known == address)
+        .map(|(_, name)| *name)
+}
+
+/// Probes all non-reserved addresses on `bus` and returns the addresses of the responding devices.
+///
+/// A device is considered present when it acknowledges an empty write to its address. Each
+/// responder gets logged along with a [`hint`] which device it might be.
+pub async fn scan<B: I2c>(bus: &mut B) -> Vec<u8, ADDRESSES> {
+    let mut responders = Vec::new();
+
+    for address in FIRST_ADDRESS..=LAST_ADDRESS {
+        if bus.write(address, &[]).await.is_ok() {
+            log::info!(
+                "I2C device at 0x{:02x} ({})",
+                address,
+                hint(address).unwrap_or("unknown")
+            );
+            // There is room for every address probed.
+            let _ = responders.push(address);
+        }
+    }
+
+    log::info!("I2C scan found {} device(s)", responders.len());
+    responders
+}
@@ -0,0 +1,252 @@
+//! Infrared remote control with the NEC protocol on the spare pins, using the remote control
+//! peripheral (RMT).
+//!
+//! Receiving needs a 38 kHz IR receiver module like the TSOP38238 or VS1838B with its output
+//! connected to _GPIO9_. Its output is idle high, which keeps the strapping pin in the state
+//! required for booting. Transmitting needs an IR LED switched by a transistor from _GPIO2_.
+//!
+//! ```rust
+//! let rmt = ir::rmt(board.peripherals.rmt)?;
+//! let mut receiver = IrReceiver::new(rmt.channel2, board.peripherals.gpio9)?;
+//! let mut transmitter = IrTransmitter::new(rmt.channel0, board.peripherals.gpio2)?;
+//!
+//! match receiver.receive().await {
+//!     Event::Command(command) => transmitter.send(command).await?,
+//!     Event::Repeat => transmitter.send_repeat().await?,
+//! }
+//! ```
+//!
+//! Most cheap remotes, for example the ones coming with Arduino kits, use the NEC protocol.
+
+use esp_hal::gpio::interconnect::{PeripheralInput, PeripheralOutput};
+use esp_hal::gpio::Level;
+use esp_hal::peripherals::RMT;
+use esp_hal::rmt::{
+    Channel, Error, PulseCode, Rmt, Rx, RxChannelConfig, RxChannelCreator, Tx, TxChannelConfig,
+    TxChannelCreator,
+};
+use esp_hal::time::Rate;
+use esp_hal::Async;
+
+/// The RMT clock. One tick is a microsecond.
+const TICK_RATE: Rate = Rate::from_mhz(1);
+
+/// The carrier frequency is about 38 kHz. The carrier phases are counted in ticks.
+const CARRIER_HALF_PERIOD: u16 = 13;
+/// Pulses shorter than this many ticks get filtered out as noise.
+const FILTER_THRESHOLD: u8 = 100;
+/// A frame is over when the line stayed idle for this many ticks. This is longer than the longest
+/// pulse of a frame.
+const IDLE_THRESHOLD: u16 = 12_000;
+
+/// The burst starting a frame or a repeat code in microseconds.
+const LEADER_MARK: u16 = 9_000;
+/// The pause after the leader burst of a frame in microseconds.
+const LEADER_SPACE: u16 = 4_500;
+/// The pause after the leader burst of a repeat code in microseconds.
+const REPEAT_SPACE: u16 = 2_250;
+/// The burst starting every bit and ending a frame in microseconds.
+const BIT_MARK: u16 = 562;
+/// The pause after the burst of a zero bit in microseconds.
+const ZERO_SPACE: u16 = 562;
+/// The pause after the burst of a one bit in microseconds.
+const ONE_SPACE: u16 = 1_687;
+
+/// The number of data bits in a frame.
+const BITS: usize = 32;
+/// The number of pulse codes of a frame: leader, data bits, and the final mark.
+const FRAME_CODES: usize = BITS + 2;
+
+/// Takes the RMT peripheral and clocks it for the NEC timing.
+pub fn rmt(rmt: RMT<'_>) -> Result<Rmt<'_, Async>, Error> {
+    Ok(Rmt::new(rmt, TICK_RATE)?.into_async())
+}
+
+/// A command from a remote control.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Command {
+    /// The address of the device. Addresses up to 255 are sent along with their complement,
+    /// larger ones use the extended NEC format with a 16 bit address.
+    pub address: u16,
+    /// The command, for example the button pressed.
+    pub command: u8,
+}
+
+impl Command {
+    /// Decodes a command from the 32 `bits` of a frame, received least significant bit first.
+    fn decode(bits: u32) -> Option<Self> {
+        let [address, address_complement, command, command_complement] = bits.to_le_bytes();
+
+        if command != !command_complement {
+            return None;
+        }
+        let address = match address == !address_complement {
+            true => u16::from(address),
+            false => u16::from_le_bytes([address, address_complement]),
+        };
+
+        Some(Self { address, command })
+    }
+
+    /// Encodes the command into the 32 bits of a frame, to be sent least significant bit first.
+    fn encode(self) -> u32 {
+        let [address, address_complement] = match u8::try_from(self.address) {
+            Ok(address) => [address, !address],
+            Err(_) => self.address.to_le_bytes(),
+        };
+
+        u32::from_le_bytes([address, address_complement, self.command, !self.command])
+    }
+}
+
+/// What got received from a remote control.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Event {
+    /// A command got sent, for example a button got pressed.
+    Command(Command),
+    /// The previous command gets repeated, for example because its button is held down. Remotes
+    /// send this every 108 ms.
+    Repeat,
+}
+
+/// Returns whether `length` is within 25 % of `expected`. This covers the tolerances of the
+/// remotes and receivers.
+fn near(length: u16, expected: u16) -> bool {
+    length.abs_diff(expected) <= expected / 4
+}
+
+/// Decodes the pulse `codes` received for a single frame. The marks are low and the spaces are
+/// high as the output of the IR receiver is active low.
+fn decode(codes: &[PulseCode]) -> Option<Event> {
+    let (leader, rest) = codes.split_first()?;
+    if !near(leader.length1(), LEADER_MARK) {
+        return None;
+    }
+
+    if near(leader.length2(), REPEAT_SPACE) {
+        return Some(Event::Repeat);
+    }
+    if !near(leader.length2(), LEADER_SPACE) || rest.len() < BITS {
+        return None;
+    }
+
+    let mut bits = 0u32;
+    for (n, code) in rest.iter().take(BITS).enumerate() {
+        if !near(code.length1(), BIT_MARK) {
+            return None;
+        }
+        if near(code.length2(), ONE_SPACE) {
+            bits |= 1 << n;
+        } else if !near(code.length2(), ZERO_SPACE) {
+            return None;
+        }
+    }
+
+    Command::decode(bits).map(Event::Command)
+}
+
+/// Receives commands from an NEC remote control through an IR receiver module.
+#[derive(Debug)]
+pub struct IrReceiver<'a> {
+    channel: Channel<'a, Async, Rx>,
+}
+
+impl<'a> IrReceiver<'a> {
+    /// Creates a new `IrReceiver` on an RMT receive channel like `channel2` and the `pin` the
+    /// receiver output is connected to.
+    pub fn new(
+        channel: impl RxChannelCreator<'a, Async>,
+        pin: impl PeripheralInput<'a>,
+    ) -> Result<Self, Error> {
+        let config = RxChannelConfig::default()
+            .with_clk_divider(1)
+            .with_filter_threshold(FILTER_THRESHOLD)
+            .with_idle_threshold(IDLE_THRESHOLD);
+        let channel = channel.configure_rx(pin, config)?;
+
+        Ok(Self { channel })
+    }
+
+    /// Waits for the next valid frame. Frames from other protocols and disturbed ones get skipped.
+    pub async fn receive(&mut self) -> Event {
+        loop {
+            let mut codes = [PulseCode::end_marker(); FRAME_CODES + 1];
+
+            match self.channel.receive(&mut codes).await {
+                Ok(count) => {
+                    let codes = codes.get(..count).unwrap_or_default();
+                    match decode(codes) {
+                        Some(event) => {
+                            log::debug!("IR: {:?}", event);
+                            return event;
+                        }
+                        None => log::trace!("IR: skipped {} pulses", count),
+                    }
+                }
+                Err(e) => log::debug!("IR: receiving failed: {:?}", e),
+            }
+        }
+    }
+}
+
+/// Sends NEC commands through an IR LED.
+#[derive(Debug)]
+pub struct IrTransmitter<'a> {
+    channel: Channel<'a, Async, Tx>,
+}
+
+impl<'a> IrTransmitter<'a> {
+    /// Creates a new `IrTransmitter` on an RMT transmit channel like `channel0` and the `pin`
+    /// switching the IR LED. The LED is on while the pin is high.
+    pub fn new(
+        channel: impl TxChannelCreator<'a, Async>,
+        pin: impl PeripheralOutput<'a>,
+    ) -> Result<Self, Error> {
+        let config = TxChannelConfig::default()
+            .with_clk_divider(1)
+            .with_idle_output(true)
+            .with_idle_output_level(Level::Low)
+            .with_carrier_modulation(true)
+            .with_carrier_high(CARRIER_HALF_PERIOD)
+            .with_carrier_low(CARRIER_HALF_PERIOD)
+            .with_carrier_level(Level::High);
+        let channel = channel.configure_tx(pin, config)?;
+
+        Ok(Self { channel })
+    }
+
+    /// Sends `command` as a single frame. This takes about 68 ms.
+    pub async fn send(&mut self, command: Command) -> Result<(), Error> {
+        let bits = command.encode();
+        let mut codes = [PulseCode::end_marker(); FRAME_CODES];
+
+        for (index, code) in codes.iter_mut().enumerate() {
+            *code = match index {
+                0 => PulseCode::new(Level::High, LEADER_MARK, Level::Low, LEADER_SPACE),
+                n if n <= BITS => {
+                    let space = match bits & (1 << (n - 1)) {
+                        0 => ZERO_SPACE,
+                        _ => ONE_SPACE,
+                    };
+                    PulseCode::new(Level::High, BIT_MARK, Level::Low, space)
+                }
+                // The final mark ends the transmission.
+                _ => PulseCode::new(Level::High, BIT_MARK, Level::Low, 0),
+            };
+        }
+
+        log::debug!("IR: sending {:?}", command);
+        self.channel.transmit(&codes).await
+    }
+
+    /// Sends a repeat code telling that the previous command is still active. Send it 108 ms
+    /// after the start of the previous frame or repeat code.
+    pub async fn send_repeat(&mut self) -> Result<(), Error> {
+        let codes = [
+            PulseCode::new(Level::High, LEADER_MARK, Level::Low, REPEAT_SPACE),
+            PulseCode::new(Level::High, BIT_MARK, Level::Low, 0),
+        ];
+
+        self.channel.transmit(&codes).await
+    }
+}
@@ -0,0 +1,73 @@
+//! Turning raw analog readings from a potentiometer into a steady knob position.
+//!
+//! Readings from the ADC jitter by a few digits even with the potentiometer left untouched.
+//! [`Knob`] smoothes them and only reports a new position after it moved by more than a
+//! hysteresis. This allows using the position directly for setting a timer duration or the number
+//! of lit storeys without flickering.
+
+/// The largest position reported by [`Knob`].
+pub const MAX_POSITION: u8 = 100;
+
+/// A smoothing filter with hysteresis scaling raw ADC readings to positions from 0 to
+/// [`MAX_POSITION`].
+#[derive(Debug)]
+pub struct Knob {
+    full_scale: u16,
+    hysteresis: u8,
+    /// The smoothed reading scaled by 2^[`Knob::SMOOTHING`] for not losing precision.
+    average: Option<u32>,
+    position: u8,
+}
+
+impl Knob {
+    /// The default hysteresis in positions.
+    pub const DEFAULT_HYSTERESIS: u8 = 2;
+
+    /// The weight of a new reading is 1/2^`SMOOTHING` of the average.
+    const SMOOTHING: u32 = 3;
+
+    /// Creates a new `Knob` for readings from zero up to `full_scale`. The ADC of the ESP32-C3
+    /// delivers 12 bit readings which gives a full scale of 4095.
+    pub fn new(full_scale: u16) -> Self {
+        Self {
+            full_scale: full_scale.max(1),
+            hysteresis: Self::DEFAULT_HYSTERESIS,
+            average: None,
+            position: 0,
+        }
+    }
+
+    /// Sets the hysteresis in positions the knob has to move before a new position gets reported.
+    pub fn with_hysteresis(self, hysteresis: u8) -> Self {
+        Self { hysteresis, ..self }
+    }
+
+    /// Returns the current position.
+    pub fn position(&self) -> u8 {
+        self.position
+    }
+
+    /// Feeds a new raw `reading` into the filter. Returns the new position if it changed by more
+    /// than the hysteresis or for the first reading.
+    pub fn update(&mut self, reading: u16) -> Option<u8> {
+        let reading = u32::from(reading.min(self.full_scale)) << Self::SMOOTHING;
+        let average = match self.average {
+            Some(average) => average - (average >> Self::SMOOTHING) + (reading >> Self::SMOOTHING),
+            None => reading,
+        };
+        let first = self.average.replace(average).is_none();
+
+        let scaled =
+            (average >> Self::SMOOTHING) * u32::from(MAX_POSITION) / u32::from(self.full_scale);
+        let position = u8::try_from(scaled).unwrap_or(MAX_POSITION);
+
+        // Snap to the ends for reaching them despite the hysteresis.
+        let at_end = (position == 0 || position == MAX_POSITION) && position != self.position;
+        if first || at_end || position.abs_diff(self.position) > self.hysteresis {
+            self.position = position;
+            Some(position)
+        } else {
+            None
+        }
+    }
+}
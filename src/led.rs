@@ -1,21 +1,200 @@
 //! Higher level abstractions for LEDs, like for cycling the storey LEDs.
 
+// Driving LEDs happens in the hot path of animations. Make sure there are no hidden panics lurking.
+#![deny(
+    clippy::expect_used,
+    clippy::indexing_slicing,
+    clippy::panic,
+    clippy::unwrap_used
+)]
+
 use crate::switch::LowActiveSwitch;
-use embassy_time::{Duration, Ticker};
+use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
+use embassy_sync::mutex::{Mutex, MutexGuard};
+use embassy_time::{Duration, Instant, Timer};
+use static_cell::StaticCell;
 
 /// The number of storey LEDs on the board.
 pub const STOREY_LEDS: usize = 8;
 
+/// The rate of animation frames or steps.
+///
+/// It is guaranteed to be non-zero and not above [`FrameRate::MAX`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct FrameRate(u32);
+
+impl FrameRate {
+    /// The highest frame rate supported: 1 kHz.
+    pub const MAX: Self = Self(1_000);
+
+    /// Creates a frame rate of `hz` frames per second. Returns `None` if `hz` is zero or above
+    /// [`FrameRate::MAX`].
+    pub const fn new(hz: u32) -> Option<Self> {
+        if hz == 0 || hz > Self::MAX.0 {
+            None
+        } else {
+            Some(Self(hz))
+        }
+    }
+
+    /// Creates a frame rate of `hz` frames per second. This is intended for constant frame rates
+    /// which get checked at compile time.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `hz` is zero or above [`FrameRate::MAX`].
+    #[allow(clippy::panic, reason = "intended for compile-time constants")]
+    pub const fn from_hz(hz: u32) -> Self {
+        match Self::new(hz) {
+            Some(rate) => rate,
+            None => panic!("invalid frame rate"),
+        }
+    }
+
+    /// The frame rate in frames per second.
+    pub const fn hz(self) -> u32 {
+        self.0
+    }
+
+    /// The period of a single frame.
+    pub const fn period(self) -> Period {
+        Period(Duration::from_hz(self.0 as u64))
+    }
+}
+
+/// The period of an animation frame or step.
+///
+/// It is guaranteed to be not shorter than [`Period::MIN`] and thus never zero.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Period(Duration);
+
+impl Period {
+    /// The shortest period supported. This is the period of [`FrameRate::MAX`].
+    pub const MIN: Self = FrameRate::MAX.period();
+
+    /// Creates a period from `duration`. Returns `None` if `duration` is shorter than
+    /// [`Period::MIN`].
+    pub const fn new(duration: Duration) -> Option<Self> {
+        if duration.as_ticks() < Self::MIN.0.as_ticks() {
+            None
+        } else {
+            Some(Self(duration))
+        }
+    }
+
+    /// Creates a period of `millis` milliseconds. This is intended for constant periods which get
+    /// checked at compile time.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the period is shorter than [`Period::MIN`].
+    #[allow(clippy::panic, reason = "intended for compile-time constants")]
+    pub const fn from_millis(millis: u64) -> Self {
+        match Self::new(Duration::from_millis(millis)) {
+            Some(period) => period,
+            None => panic!("period too short"),
+        }
+    }
+
+    /// Creates a period of `secs` seconds. This is intended for constant periods which get
+    /// checked at compile time.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `secs` is zero.
+    #[allow(clippy::panic, reason = "intended for compile-time constants")]
+    pub const fn from_secs(secs: u64) -> Self {
+        match Self::new(Duration::from_secs(secs)) {
+            Some(period) => period,
+            None => panic!("period too short"),
+        }
+    }
+
+    /// The period as plain duration.
+    pub const fn as_duration(self) -> Duration {
+        self.0
+    }
+}
+
+impl From<FrameRate> for Period {
+    fn from(rate: FrameRate) -> Self {
+        rate.period()
+    }
+}
+
+/// Paces animations at a fixed frame rate.
+///
+/// Frames are aligned to multiples of the frame period since boot. So all animations using the
+/// same period stay in phase, no matter when they got started. Frames which got missed because of
+/// the animation taking too long are skipped instead of being caught up with and counted in
+/// [`Ticker::skipped`].
+#[derive(Debug)]
+pub struct Ticker {
+    period: Period,
+    frame: u64,
+    skipped: u64,
+}
+
+impl Ticker {
+    /// Creates a ticker for frames every `period`. This may be given as [`FrameRate`] as well.
+    pub fn every(period: impl Into<Period>) -> Self {
+        let period = period.into();
+        let frame = Instant::now().as_ticks() / period.as_duration().as_ticks();
+
+        Self {
+            period,
+            frame,
+            skipped: 0,
+        }
+    }
+
+    /// Waits for the start of the next frame and returns its number.
+    pub async fn next(&mut self) -> u64 {
+        let period = self.period.as_duration().as_ticks();
+        let due = Instant::now().as_ticks() / period + 1;
+        let expected = self.frame.saturating_add(1);
+
+        if due > expected {
+            log::trace!("skipped {} frame(s)", due - expected);
+            self.skipped = self.skipped.saturating_add(due - expected);
+        }
+        self.frame = due;
+
+        Timer::at(Instant::from_ticks(due.saturating_mul(period))).await;
+        self.frame
+    }
+
+    /// The number of the current frame.
+    pub fn frame(&self) -> u64 {
+        self.frame
+    }
+
+    /// The frame period.
+    pub fn period(&self) -> Period {
+        self.period
+    }
+
+    /// The total number of frames skipped so far.
+    pub fn skipped(&self) -> u64 {
+        self.skipped
+    }
+}
+
 /// Convenience wrapper proviving higher-level functionality for all the storey LEDs like for
 /// example cycling one switched on led.
 #[derive(Debug)]
 pub struct Storeys<'a> {
     leds: [LowActiveSwitch<'a>; 8],
+    /// The pattern currently shown or `None` if the state of the LEDs is not known yet.
+    pattern: Option<u8>,
 }
 
 impl<'a> Storeys<'a> {
     pub fn new(leds: [LowActiveSwitch<'a>; 8]) -> Self {
-        Self { leds }
+        Self {
+            leds,
+            pattern: None,
+        }
     }
 
     pub fn free(self) -> [LowActiveSwitch<'a>; 8] {
@@ -25,13 +204,13 @@ impl<'a> Storeys<'a> {
     /// Switches all storey LEDs off.
     pub fn all_off(&mut self) {
         log::debug!("Dn off");
-        self.leds.iter_mut().for_each(|led| led.switch_off());
+        self.set_pattern(0);
     }
 
     /// Switches all storey LEDs on.
     pub fn all_on(&mut self) {
         log::debug!("Dn on");
-        self.leds.iter_mut().for_each(|led| led.switch_on());
+        self.set_pattern(u8::MAX);
     }
 
     /// Blinks all storey LEDs simultaneously.
@@ -39,7 +218,7 @@ impl<'a> Storeys<'a> {
     /// Blinking is performed until the returned future is dropped. So `await`ing this future alone
     /// will block forever. Use [`embassy_futures::select::select`] and friends to blink the LEDs
     /// while waiting for some other event to happen.
-    pub async fn blink(&mut self, step: Duration) {
+    pub async fn blink(&mut self, step: impl Into<Period>) {
         let mut ticker = Ticker::every(step);
 
         loop {
@@ -55,30 +234,128 @@ impl<'a> Storeys<'a> {
     /// Cycling is performed until the returned future is dropped. So `await`ing this future alone
     /// will block forever. Use [`embassy_futures::select::select`] and friends to cycle the LEDs
     /// while waiting for some other event to happen.
-    pub async fn cycle(&mut self, step: Duration) {
+    pub async fn cycle(&mut self, step: impl Into<Period>) {
         let mut ticker = Ticker::every(step);
 
         self.all_off();
 
         loop {
-            for n in 0..self.leds.len() {
+            for n in 0..STOREY_LEDS as u32 {
                 log::debug!("cycle D{}", n + 1);
-
-                let previous = (n + self.leds.len() - 1) % self.leds.len();
-                self.leds[previous].switch_off();
-                self.leds[n].switch_on();
+                self.set_pattern(1u8.wrapping_shl(n));
                 ticker.next().await;
             }
         }
     }
 
+    /// Blinks out `code` once, so that it can be read off the LEDs. For example for telling apart
+    /// boards by their [`crate::board::device_id`] in a room full of them.
+    ///
+    /// The code is framed by all LEDs lit for three times `step`. In between, every byte is shown
+    /// as bit pattern (see [`Storeys::set_pattern`]) for `step`, followed by all LEDs off for
+    /// `step`. So a byte `0xff` lit only for a single step is still distinguishable from the frame.
+    pub async fn blink_out(&mut self, code: &[u8], step: impl Into<Period>) {
+        let step = step.into().as_duration();
+        let frame = step * 3;
+
+        log::debug!("blink out {:02x?}", code);
+        self.all_on();
+        Timer::after(frame).await;
+        self.all_off();
+        Timer::after(step).await;
+
+        for byte in code {
+            self.set_pattern(*byte);
+            Timer::after(step).await;
+            self.all_off();
+            Timer::after(step).await;
+        }
+
+        self.all_on();
+        Timer::after(frame).await;
+        self.all_off();
+    }
+
     /// Sets the storey LEDs to the supplied pattern. The bit at index _n_ specifies the output
     /// state of the LED at index _n_ from the array `leds` passed to [`Storeys::new`].
+    ///
+    /// Only the LEDs differing from the previous pattern get switched. This keeps the overhead low
+    /// for animations running at high frame rates.
     pub fn set_pattern(&mut self, pattern: u8) {
-        for i in 0..self.leds.len() {
-            let bit = (pattern >> i) & 1;
-            let on = bit == 1;
-            self.leds[i].switch(on);
+        let changed = match self.pattern {
+            Some(previous) => previous ^ pattern,
+            None => u8::MAX,
+        };
+        let mut mask = 1u8;
+
+        for led in self.leds.iter_mut() {
+            if changed & mask != 0 {
+                led.switch(pattern & mask != 0);
+            }
+            mask = mask.wrapping_shl(1);
+        }
+
+        self.pattern = Some(pattern);
+    }
+
+    /// Returns the pattern currently shown or `None` if no pattern has been set yet. See
+    /// [`Storeys::set_pattern`] for its layout.
+    pub fn pattern(&self) -> Option<u8> {
+        self.pattern
+    }
+}
+
+static SHARED_STOREYS: StaticCell<Mutex<CriticalSectionRawMutex, Storeys<'static>>> =
+    StaticCell::new();
+
+/// A handle for sharing the storey LEDs between multiple tasks.
+///
+/// The handle is cheap to copy and can be passed to as many tasks as needed. Each access locks
+/// the storey LEDs for the time of the operation. Use [`SharedStoreys::lock`] for running a
+/// longer animation without being interfered by others.
+///
+/// ```rust
+/// let storeys = SharedStoreys::new(Storeys::new(board.storey_leds)).unwrap();
+///
+/// task::spawn_or_report(&spawner, "app", app_task(storeys));
+/// task::spawn_or_report(&spawner, "error blinker", error_blinker_task(storeys));
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct SharedStoreys {
+    storeys: &'static Mutex<CriticalSectionRawMutex, Storeys<'static>>,
+}
+
+impl SharedStoreys {
+    /// Moves `storeys` into the shared (static) storage and returns a handle to it.
+    ///
+    /// There is only one set of storey LEDs on the board and so they can only be shared once. An
+    /// error containing `storeys` is returned when they have already been shared before.
+    pub fn new(storeys: Storeys<'static>) -> Result<Self, Storeys<'static>> {
+        match SHARED_STOREYS.try_uninit() {
+            Some(cell) => Ok(Self {
+                storeys: cell.write(Mutex::new(storeys)),
+            }),
+            None => Err(storeys),
         }
     }
+
+    /// Waits for exclusive access to the storey LEDs.
+    pub async fn lock(&self) -> MutexGuard<'static, CriticalSectionRawMutex, Storeys<'static>> {
+        self.storeys.lock().await
+    }
+
+    /// Switches all storey LEDs off. See [`Storeys::all_off`].
+    pub async fn all_off(&self) {
+        self.lock().await.all_off();
+    }
+
+    /// Switches all storey LEDs on. See [`Storeys::all_on`].
+    pub async fn all_on(&self) {
+        self.lock().await.all_on();
+    }
+
+    /// Sets the storey LEDs to the supplied pattern. See [`Storeys::set_pattern`].
+    pub async fn set_pattern(&self, pattern: u8) {
+        self.lock().await.set_pattern(pattern);
+    }
 }
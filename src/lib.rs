@@ -9,9 +9,56 @@
 //!
 //! Congratulations! Now have a look at [`board::Board`] to see what you just got and where to go
 //! on from here. Have fun!
+//!
+//! # Features
+//!
+//! * `alloc` (default): Sets up a global heap allocator during board initialization. Build with
+//!   `--no-default-features` for firmware which gets along with static memory only. The [`eol`]
+//!   checks need this feature.
+//! * `log-timestamp` (default): Prefixes every line of log output with the milliseconds since
+//!   boot. Firmware built with this feature needs to link this crate, as it provides the time for
+//!   the log output.
+//!
+//! # Time
+//!
+//! Timekeeping is based on [`embassy_time`] with 64 bit ticks at 1 MHz counting from reset. This
+//! doesn't wrap around for more than 500 000 years, so deadlines and multi-day countdowns can be
+//! computed from [`embassy_time::Instant`]s without taking care of wrapping. Mind that
+//! [`embassy_time::Instant::duration_since`] and subtracting a larger from a smaller
+//! [`embassy_time::Duration`] panic. Use `saturating_duration_since` or `checked_sub` when the
+//! order is not guaranteed, for example after a task got delayed.
 
 #![no_std]
 
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+pub mod actuator;
 pub mod board;
+pub mod bus;
+pub mod climate;
+pub mod config;
+pub mod display;
+pub mod dmx;
+#[cfg(feature = "alloc")]
+pub mod eol;
+pub mod graphics;
+pub mod i2c;
+pub mod ir;
+pub mod knob;
 pub mod led;
+pub mod midi;
+pub mod notify;
+pub mod onewire;
+pub mod pir;
+pub mod rtc;
+pub mod sensor;
+pub mod shake;
 pub mod switch;
+pub mod system;
+pub mod task;
+pub mod trace;
+pub mod ultrasonic;
+pub mod version;
+
+pub use version::version;
@@ -0,0 +1,91 @@
+//! A MIDI output for playing notes on synthesizers and computers from the secondary UART.
+//!
+//! Connect a 5-pin DIN socket with pin 4 through a 33 Ω resistor to 3.3 V and pin 5 through a
+//! 10 Ω resistor to _GPIO9_, as the MIDI specification describes for 3.3 V devices. The line is
+//! idle high, which keeps the strapping pin in the state required for booting. A USB MIDI
+//! interface brings the notes to a computer.
+//!
+//! ```rust
+//! let mut midi = MidiOutput::new(board.peripherals.uart1, board.peripherals.gpio9)?;
+//!
+//! midi.note_on(0, midi::MIDDLE_C, 100).await?;
+//! Timer::after_millis(500).await;
+//! midi.note_off(0, midi::MIDDLE_C).await?;
+//! ```
+
+use esp_hal::peripherals::{GPIO9, UART1};
+use esp_hal::uart::{Config, ConfigError, TxError, UartTx};
+use esp_hal::Async;
+
+/// The MIDI line rate in bits per second.
+const BAUDRATE: u32 = 31_250;
+
+/// The status byte for releasing a note, followed by the channel in the low nibble.
+const NOTE_OFF: u8 = 0x80;
+/// The status byte for starting a note, followed by the channel in the low nibble.
+const NOTE_ON: u8 = 0x90;
+/// The status byte for changing a controller value, followed by the channel in the low nibble.
+const CONTROL_CHANGE: u8 = 0xb0;
+
+/// The note number of the middle C (C4).
+pub const MIDDLE_C: u8 = 60;
+/// The channel for percussion in the General MIDI standard, counting from zero.
+pub const PERCUSSION_CHANNEL: u8 = 9;
+
+/// A MIDI output sending channel messages.
+///
+/// Channels are counted from zero, so channel _n_ from the MIDI specification is _n - 1_ here.
+/// Channels above 15 and data values above 127 get truncated to their valid bits.
+pub struct MidiOutput<'a> {
+    uart: UartTx<'a, Async>,
+}
+
+impl<'a> MidiOutput<'a> {
+    /// Creates a new `MidiOutput` sending on _GPIO9_.
+    pub fn new(uart1: UART1<'a>, tx: GPIO9<'a>) -> Result<Self, ConfigError> {
+        let config = Config::default().with_baudrate(BAUDRATE);
+        let uart = UartTx::new(uart1, config)?.with_tx(tx).into_async();
+
+        Ok(Self { uart })
+    }
+
+    /// Starts playing `note` on `channel` with `velocity`. A velocity of zero releases the note
+    /// like [`MidiOutput::note_off`].
+    pub async fn note_on(&mut self, channel: u8, note: u8, velocity: u8) -> Result<(), TxError> {
+        self.send(NOTE_ON, channel, note, velocity).await
+    }
+
+    /// Releases `note` on `channel`.
+    pub async fn note_off(&mut self, channel: u8, note: u8) -> Result<(), TxError> {
+        self.send(NOTE_OFF, channel, note, 0).await
+    }
+
+    /// Sets `controller` on `channel` to `value`, for example 1 for the modulation wheel.
+    pub async fn control_change(
+        &mut self,
+        channel: u8,
+        controller: u8,
+        value: u8,
+    ) -> Result<(), TxError> {
+        self.send(CONTROL_CHANGE, channel, controller, value).await
+    }
+
+    /// Sends a three byte channel message and waits until it has been sent. This takes about
+    /// 1 ms.
+    async fn send(
+        &mut self,
+        status: u8,
+        channel: u8,
+        first: u8,
+        second: u8,
+    ) -> Result<(), TxError> {
+        let mut bytes: &[u8] = &[status | (channel & 0x0f), first & 0x7f, second & 0x7f];
+        log::debug!("MIDI: {:02x?}", bytes);
+
+        while !bytes.is_empty() {
+            let written = self.uart.write_async(bytes).await?;
+            bytes = bytes.get(written..).unwrap_or_default();
+        }
+        self.uart.flush_async().await
+    }
+}
@@ -0,0 +1,113 @@
+//! Alerts for getting the user's attention, for example when a timer expired.
+
+use core::convert::Infallible;
+use core::future::Future;
+
+use embassy_futures::select::{select, Either};
+use embassy_time::{Duration, Ticker, Timer};
+
+use crate::led::{Period, Storeys};
+
+/// An alert policy starting with a subtle flash and escalating to blinking all storey LEDs until
+/// the user acknowledges it.
+///
+/// ```rust
+/// let acknowledged = select(button.wait_for_falling_edge(), shake.wait_for_shake());
+/// Escalation::default().alert(&mut storeys, acknowledged).await;
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct Escalation {
+    subtle_for: Duration,
+    flash_every: Period,
+    blink_step: Period,
+}
+
+impl Escalation {
+    /// The default time for the subtle stage before escalating.
+    pub const DEFAULT_SUBTLE_FOR: Duration = Duration::from_secs(30);
+    /// The default interval between the flashes of the subtle stage.
+    pub const DEFAULT_FLASH_EVERY: Period = Period::from_secs(2);
+    /// The default step for blinking all storey LEDs when escalated.
+    pub const DEFAULT_BLINK_STEP: Period = Period::from_millis(250);
+
+    /// How long the storey LEDs are lit for a flash of the subtle stage.
+    const FLASH: Duration = Duration::from_millis(50);
+
+    /// Creates a new `Escalation` with default timing.
+    pub fn new() -> Self {
+        Self {
+            subtle_for: Self::DEFAULT_SUBTLE_FOR,
+            flash_every: Self::DEFAULT_FLASH_EVERY,
+            blink_step: Self::DEFAULT_BLINK_STEP,
+        }
+    }
+
+    /// Sets for how long the alert stays subtle before escalating.
+    pub fn with_subtle_for(self, subtle_for: Duration) -> Self {
+        Self { subtle_for, ..self }
+    }
+
+    /// Sets the interval between the flashes of the subtle stage.
+    pub fn with_flash_every(self, flash_every: impl Into<Period>) -> Self {
+        Self {
+            flash_every: flash_every.into(),
+            ..self
+        }
+    }
+
+    /// Sets the step for blinking all storey LEDs when escalated.
+    pub fn with_blink_step(self, blink_step: impl Into<Period>) -> Self {
+        Self {
+            blink_step: blink_step.into(),
+            ..self
+        }
+    }
+
+    /// Alerts on `storeys` until `acknowledged` completes. The storey LEDs get switched off
+    /// afterwards.
+    pub async fn alert<F: Future>(&self, storeys: &mut Storeys<'_>, acknowledged: F) -> F::Output {
+        let output = match select(acknowledged, self.escalate(storeys)).await {
+            Either::First(output) => output,
+            Either::Second(never) => match never {},
+        };
+
+        log::debug!("alert acknowledged");
+        storeys.all_off();
+        output
+    }
+
+    /// Runs through the stages of the alert.
+    async fn escalate(&self, storeys: &mut Storeys<'_>) -> Infallible {
+        log::debug!("alert: subtle");
+        if let Either::First(never) =
+            select(self.flash(storeys), Timer::after(self.subtle_for)).await
+        {
+            match never {}
+        }
+
+        log::debug!("alert: escalated");
+        loop {
+            storeys.blink(self.blink_step).await;
+        }
+    }
+
+    /// Briefly flashes all storey LEDs right away and then every `flash_every`.
+    async fn flash(&self, storeys: &mut Storeys<'_>) -> Infallible {
+        // Counts from now on. A frame ticker aligned to the time since boot could tick right after
+        // the first flash and flash twice in a row.
+        let mut ticker = Ticker::every(self.flash_every.as_duration());
+
+        loop {
+            storeys.all_on();
+            Timer::after(Self::FLASH).await;
+            storeys.all_off();
+            ticker.next().await;
+        }
+    }
+}
+
+impl Default for Escalation {
+    fn default() -> Self {
+        Self::new()
+    }
+}
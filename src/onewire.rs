@@ -0,0 +1,346 @@
+//! A 1-Wire bus on a spare pin and the DS18B20 temperature sensor.
+//!
+//! 1-Wire uses a single open-drain data line with a 4.7 kΩ pull-up resistor to 3V3. The line gets
+//! bit-banged by [`OneWire`], so connect it to _GPIO2_. A device pulling _GPIO9_ low during reset
+//! would prevent booting the firmware.
+//!
+//! ```rust
+//! let bus = OneWire::new(board.peripherals.gpio2);
+//! let mut sensor = Ds18b20::new(bus);
+//!
+//! let celsius = sensor.measure().await?;
+//! ```
+//!
+//! A [`Ds18b20`] is a [`Sensor`] and can be sampled by a [`crate::sensor::Poller`].
+
+use embassy_time::{Duration, Timer};
+use esp_hal::delay::Delay;
+use esp_hal::gpio::{DriveMode, Flex, InputConfig, OutputConfig, Pin, Pull};
+use heapless::Vec;
+
+use crate::sensor::{Sensor, Value};
+
+/// Returns all devices on the bus.
+const SEARCH_ROM: u8 = 0xf0;
+/// Addresses the single device on the bus.
+const READ_ROM: u8 = 0x33;
+/// Addresses the device with the following ROM code.
+const MATCH_ROM: u8 = 0x55;
+/// Addresses all devices on the bus.
+const SKIP_ROM: u8 = 0xcc;
+
+/// The 64 bit ROM code identifying a device on the bus. Its lowest byte is the family code.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Address(pub u64);
+
+impl Address {
+    /// The family code telling the type of the device, for example `0x28` for a DS18B20.
+    pub fn family(self) -> u8 {
+        (self.0 & 0xff) as u8
+    }
+}
+
+/// Errors from talking to 1-Wire devices.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Error {
+    /// No device answered the reset pulse.
+    NoPresence,
+    /// The data got corrupted on the way.
+    Crc,
+    /// Only zeros got read, for example from a data line shorted to ground. Their CRC would match.
+    NoData,
+    /// The temperature still holds its power-on value, so no conversion took place. For example
+    /// because the sensor lacks power for it.
+    NotConverted,
+}
+
+/// A bit-banged 1-Wire bus master.
+///
+/// The bit timing is generated by busy waiting with interrupts disabled for the duration of a
+/// single bit (up to 70 µs). So other tasks don't get delayed for longer while talking to a device.
+#[derive(Debug)]
+pub struct OneWire<'a> {
+    pin: Flex<'a>,
+}
+
+impl<'a> OneWire<'a> {
+    /// Creates a new `OneWire` bus on `pin`. The line needs an external pull-up.
+    pub fn new(pin: impl Pin + 'a) -> Self {
+        let mut pin = Flex::new(pin);
+        pin.set_high();
+        pin.apply_output_config(
+            &OutputConfig::default()
+                .with_drive_mode(DriveMode::OpenDrain)
+                .with_pull(Pull::None),
+        );
+        pin.apply_input_config(&InputConfig::default());
+        pin.set_input_enable(true);
+        pin.set_output_enable(true);
+
+        Self { pin }
+    }
+
+    /// Returns the pin of the bus.
+    pub fn free(self) -> Flex<'a> {
+        self.pin
+    }
+
+    /// Sends a reset pulse. Returns whether at least one device answered with a presence pulse.
+    pub fn reset(&mut self) -> bool {
+        self.pin.set_low();
+        delay(480);
+
+        let present = critical_section::with(|_| {
+            self.pin.set_high();
+            delay(70);
+            self.pin.is_low()
+        });
+        delay(410);
+
+        present
+    }
+
+    /// Writes a single bit.
+    pub fn write_bit(&mut self, bit: bool) {
+        critical_section::with(|_| {
+            self.pin.set_low();
+            if bit {
+                delay(6);
+                self.pin.set_high();
+                delay(64);
+            } else {
+                delay(60);
+                self.pin.set_high();
+                delay(10);
+            }
+        });
+    }
+
+    /// Reads a single bit.
+    pub fn read_bit(&mut self) -> bool {
+        critical_section::with(|_| {
+            self.pin.set_low();
+            delay(6);
+            self.pin.set_high();
+            delay(9);
+            let bit = self.pin.is_high();
+            delay(55);
+            bit
+        })
+    }
+
+    /// Writes `byte`, least significant bit first.
+    pub fn write_byte(&mut self, byte: u8) {
+        for n in 0..8 {
+            self.write_bit(byte & (1 << n) != 0);
+        }
+    }
+
+    /// Reads a byte, least significant bit first.
+    pub fn read_byte(&mut self) -> u8 {
+        (0..8).fold(0, |byte, n| byte | (u8::from(self.read_bit()) << n))
+    }
+
+    /// Resets the bus and addresses the device at `address` or all devices if it is `None`. The
+    /// next byte written is the command for the device.
+    pub fn select(&mut self, address: Option<Address>) -> Result<(), Error> {
+        if !self.reset() {
+            return Err(Error::NoPresence);
+        }
+
+        match address {
+            Some(address) => {
+                self.write_byte(MATCH_ROM);
+                address
+                    .0
+                    .to_le_bytes()
+                    .iter()
+                    .for_each(|b| self.write_byte(*b));
+            }
+            None => self.write_byte(SKIP_ROM),
+        }
+
+        Ok(())
+    }
+
+    /// Reads the address of the single device on the bus. This gives garbage if there are more.
+    pub fn read_address(&mut self) -> Result<Address, Error> {
+        if !self.reset() {
+            return Err(Error::NoPresence);
+        }
+
+        self.write_byte(READ_ROM);
+        let mut rom = [0; 8];
+        rom.iter_mut().for_each(|b| *b = self.read_byte());
+        check_crc(&rom)?;
+
+        Ok(Address(u64::from_le_bytes(rom)))
+    }
+
+    /// Searches for the devices on the bus and returns the addresses of up to `N`.
+    pub fn search<const N: usize>(&mut self) -> Result<Vec<Address, N>, Error> {
+        let mut found = Vec::new();
+        let mut rom = 0u64;
+        // The bit position where the last search took the 0 branch while both were possible.
+        let mut last_discrepancy = None;
+
+        while !found.is_full() {
+            if !self.reset() {
+                return Err(Error::NoPresence);
+            }
+            self.write_byte(SEARCH_ROM);
+
+            let mut discrepancy = None;
+            for n in 0..64 {
+                let bit = self.read_bit();
+                let complement = self.read_bit();
+
+                let take = match (bit, complement) {
+                    // No device participating anymore.
+                    (true, true) => return Ok(found),
+                    // All remaining devices agree on this bit.
+                    (bit, complement) if bit != complement => bit,
+                    // Devices differ. Repeat the previous path up to the last discrepancy, take 1
+                    // there, and 0 after it.
+                    _ => {
+                        let take = match last_discrepancy {
+                            Some(last) if n < last => rom & (1 << n) != 0,
+                            Some(last) => n == last,
+                            None => false,
+                        };
+                        if !take {
+                            discrepancy = Some(n);
+                        }
+                        take
+                    }
+                };
+
+                if take {
+                    rom |= 1 << n;
+                } else {
+                    rom &= !(1 << n);
+                }
+                self.write_bit(take);
+            }
+
+            check_crc(&rom.to_le_bytes())?;
+            // There is room as checked by the loop condition.
+            let _ = found.push(Address(rom));
+
+            if discrepancy.is_none() {
+                break;
+            }
+            last_discrepancy = discrepancy;
+        }
+
+        Ok(found)
+    }
+}
+
+/// Busy waits for `micros` microseconds.
+fn delay(micros: u32) {
+    Delay::new().delay_micros(micros);
+}
+
+/// Checks the CRC in the last byte of `data`.
+fn check_crc(data: &[u8]) -> Result<(), Error> {
+    // The CRC over data including its CRC is zero.
+    match crc8(data) {
+        0 => Ok(()),
+        _ => Err(Error::Crc),
+    }
+}
+
+/// Computes the Maxim/Dallas CRC-8 (polynomial x^8 + x^5 + x^4 + 1) over `data`.
+fn crc8(data: &[u8]) -> u8 {
+    data.iter().fold(0, |crc, byte| {
+        (0..8).fold(crc ^ byte, |crc, _| match crc & 1 {
+            0 => crc >> 1,
+            _ => (crc >> 1) ^ 0x8c,
+        })
+    })
+}
+
+/// A DS18B20 temperature sensor on a [`OneWire`] bus.
+#[derive(Debug)]
+pub struct Ds18b20<'a> {
+    bus: OneWire<'a>,
+    address: Option<Address>,
+}
+
+impl<'a> Ds18b20<'a> {
+    /// The family code of the DS18B20 in its [`Address`].
+    pub const FAMILY: u8 = 0x28;
+
+    /// Starts converting the temperature.
+    const CONVERT_T: u8 = 0x44;
+    /// Reads the scratchpad containing the converted temperature.
+    const READ_SCRATCHPAD: u8 = 0xbe;
+    /// The time for converting at the default 12 bit resolution.
+    const CONVERSION: Duration = Duration::from_millis(750);
+    /// The temperature register value after power-on, reading as 85 °C.
+    const POWER_ON_VALUE: i16 = 0x0550;
+
+    /// Creates a new `Ds18b20` as the single device on `bus`.
+    pub fn new(bus: OneWire<'a>) -> Self {
+        Self { bus, address: None }
+    }
+
+    /// Addresses the sensor at `address`, for example from [`OneWire::search`], for telling it
+    /// apart from others on the same bus.
+    pub fn with_address(self, address: Address) -> Self {
+        Self {
+            address: Some(address),
+            ..self
+        }
+    }
+
+    /// Returns the underlying bus.
+    pub fn free(self) -> OneWire<'a> {
+        self.bus
+    }
+
+    /// Measures the temperature in degrees Celsius. This takes 750 ms for converting it.
+    ///
+    /// A reading of exactly 85 °C is reported as [`Error::NotConverted`], as it can't be told apart
+    /// from the power-on value.
+    pub async fn measure(&mut self) -> Result<f32, Error> {
+        self.bus.select(self.address)?;
+        self.bus.write_byte(Self::CONVERT_T);
+        Timer::after(Self::CONVERSION).await;
+
+        self.bus.select(self.address)?;
+        self.bus.write_byte(Self::READ_SCRATCHPAD);
+        let mut scratchpad = [0; 9];
+        scratchpad
+            .iter_mut()
+            .for_each(|b| *b = self.bus.read_byte());
+        if scratchpad.iter().all(|&b| b == 0) {
+            return Err(Error::NoData);
+        }
+        check_crc(&scratchpad)?;
+
+        // The temperature is a signed fixed point number with four fractional bits.
+        let raw = i16::from_le_bytes([scratchpad[0], scratchpad[1]]);
+        if raw == Self::POWER_ON_VALUE {
+            return Err(Error::NotConverted);
+        }
+        Ok(f32::from(raw) / 16.0)
+    }
+}
+
+impl Sensor for Ds18b20<'_> {
+    fn name(&self) -> &'static str {
+        "ds18b20"
+    }
+
+    async fn sample(&mut self) -> Option<Value> {
+        match self.measure().await {
+            Ok(celsius) => Some(Value::Temperature(celsius)),
+            Err(e) => {
+                log::debug!("DS18B20: {:?}", e);
+                None
+            }
+        }
+    }
+}
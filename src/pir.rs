@@ -0,0 +1,97 @@
+//! Support for passive infrared (PIR) motion sensors like the HC-SR501 connected to a spare pin.
+//!
+//! These sensors drive their output high while they detect motion. A person sitting still will
+//! not trigger them continuously, so the [`MotionSensor`] only reports presence as cleared when
+//! there was no motion for a hold-off time.
+//!
+//! ```rust
+//! let input = Input::new(board.peripherals.gpio2, InputConfig::default().with_pull(Pull::Down));
+//! let mut pir = MotionSensor::new(input).with_hold_off(Duration::from_secs(60));
+//!
+//! loop {
+//!     match pir.next().await {
+//!         Presence::Detected => storeys.all_on(),
+//!         Presence::Cleared => storeys.all_off(),
+//!     }
+//! }
+//! ```
+
+use embassy_futures::select::{select, Either};
+use embassy_time::{Duration, Timer};
+use esp_hal::gpio::Input;
+
+/// A change of presence reported from [`MotionSensor::next`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Presence {
+    /// Motion got detected after presence was cleared.
+    Detected,
+    /// There was no motion for the hold-off time.
+    Cleared,
+}
+
+/// A PIR motion sensor with an active-high output.
+#[derive(Debug)]
+pub struct MotionSensor<'a> {
+    input: Input<'a>,
+    hold_off: Duration,
+    present: bool,
+}
+
+impl<'a> MotionSensor<'a> {
+    /// The default time without motion before presence gets cleared.
+    pub const DEFAULT_HOLD_OFF: Duration = Duration::from_secs(30);
+
+    /// Creates a new `MotionSensor` from the input the sensor output is connected to. Presence
+    /// starts out as cleared.
+    pub fn new(input: Input<'a>) -> Self {
+        Self {
+            input,
+            hold_off: Self::DEFAULT_HOLD_OFF,
+            present: false,
+        }
+    }
+
+    /// Sets the time without motion before presence gets cleared.
+    pub fn with_hold_off(self, hold_off: Duration) -> Self {
+        Self { hold_off, ..self }
+    }
+
+    /// Returns the input the sensor is connected to for using esp-hal functionality not covered
+    /// here.
+    pub fn input_mut(&mut self) -> &mut Input<'a> {
+        &mut self.input
+    }
+
+    /// Returns the input the sensor is connected to.
+    pub fn free(self) -> Input<'a> {
+        self.input
+    }
+
+    /// Whether presence is currently detected.
+    pub fn is_present(&self) -> bool {
+        self.present
+    }
+
+    /// Waits for the next change of presence.
+    pub async fn next(&mut self) -> Presence {
+        if !self.present {
+            self.input.wait_for_high().await;
+            self.present = true;
+            log::debug!("presence detected");
+            return Presence::Detected;
+        }
+
+        loop {
+            self.input.wait_for_low().await;
+
+            match select(Timer::after(self.hold_off), self.input.wait_for_high()).await {
+                Either::First(_) => break,
+                Either::Second(_) => log::trace!("motion within hold-off"),
+            }
+        }
+
+        self.present = false;
+        log::debug!("presence cleared");
+        Presence::Cleared
+    }
+}
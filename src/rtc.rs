@@ -0,0 +1,263 @@
+//! Support for the DS3231 real-time clock on the I2C bus.
+//!
+//! The DS3231 keeps the time from a backup battery while the board is powered off and drifts by
+//! less than a minute per year thanks to its temperature compensated oscillator. Connect it to
+//! the bus from [`crate::i2c::open`].
+//!
+//! ```rust
+//! let bus = i2c::open(board.peripherals.i2c0, board.peripherals.gpio2, board.peripherals.gpio9)?;
+//! let mut rtc = Ds3231::new(bus);
+//!
+//! if let Some(now) = rtc.now().await? {
+//!     log::info!("it is {}", now);
+//! }
+//! ```
+//!
+//! The crate has no network time synchronization, so the time has to be set with
+//! [`Ds3231::set`], for example from [`crate::version::Version::build_timestamp`].
+
+use core::fmt;
+
+use embedded_hal_async::i2c::I2c;
+
+use crate::sensor::{Sensor, Value};
+
+/// The number of days from 0000-03-01 to the Unix epoch in the proleptic Gregorian calendar.
+const DAYS_TO_UNIX_EPOCH: u64 = 719_468;
+/// The number of days in a 400 year cycle of the Gregorian calendar.
+const DAYS_PER_ERA: u64 = 146_097;
+/// The number of seconds in a day.
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+/// A date and time in UTC, from 2000 to 2199 as kept by the DS3231.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct DateTime {
+    /// The year, for example 2025.
+    pub year: u16,
+    /// The month from 1 to 12.
+    pub month: u8,
+    /// The day of the month from 1 to 31.
+    pub day: u8,
+    /// The hour from 0 to 23.
+    pub hour: u8,
+    /// The minute from 0 to 59.
+    pub minute: u8,
+    /// The second from 0 to 59.
+    pub second: u8,
+}
+
+impl DateTime {
+    /// Converts `seconds` since the Unix epoch. Returns `None` if this is not within 2000 to 2199.
+    pub fn from_unix(seconds: u64) -> Option<Self> {
+        let days = seconds / SECONDS_PER_DAY;
+        let time = seconds % SECONDS_PER_DAY;
+
+        // See <https://howardhinnant.github.io/date_algorithms.html#civil_from_days>, computing
+        // with years starting in March for having the leap day at their end.
+        let days = days + DAYS_TO_UNIX_EPOCH;
+        let era = days / DAYS_PER_ERA;
+        let day_of_era = days % DAYS_PER_ERA;
+        let year_of_era =
+            (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146_096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let march_month = (5 * day_of_year + 2) / 153;
+        let day = day_of_year - (153 * march_month + 2) / 5 + 1;
+        let month = if march_month < 10 {
+            march_month + 3
+        } else {
+            march_month - 9
+        };
+        let year = era * 400 + year_of_era + u64::from(month <= 2);
+
+        let date_time = Self {
+            year: u16::try_from(year).ok()?,
+            month: month as u8,
+            day: day as u8,
+            hour: (time / 3600) as u8,
+            minute: (time / 60 % 60) as u8,
+            second: (time % 60) as u8,
+        };
+        (2000..=2199).contains(&date_time.year).then_some(date_time)
+    }
+
+    /// Returns the seconds since the Unix epoch.
+    pub fn to_unix(self) -> u64 {
+        // See <https://howardhinnant.github.io/date_algorithms.html#days_from_civil>.
+        let month = u64::from(self.month.clamp(1, 12));
+        let year = u64::from(self.year) - u64::from(month <= 2);
+        let era = year / 400;
+        let year_of_era = year % 400;
+        let march_month = (month + 9) % 12;
+        let day_of_year = (153 * march_month + 2) / 5 + u64::from(self.day.max(1)) - 1;
+        let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+        let days = (era * DAYS_PER_ERA + day_of_era).saturating_sub(DAYS_TO_UNIX_EPOCH);
+
+        days * SECONDS_PER_DAY
+            + u64::from(self.hour) * 3600
+            + u64::from(self.minute) * 60
+            + u64::from(self.second)
+    }
+
+    /// Returns the day of the week from 1 (Monday) to 7 (Sunday).
+    pub fn weekday(self) -> u8 {
+        // The Unix epoch was a Thursday.
+        ((self.to_unix() / SECONDS_PER_DAY + 3) % 7 + 1) as u8
+    }
+}
+
+impl fmt::Display for DateTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
+            self.year, self.month, self.day, self.hour, self.minute, self.second
+        )
+    }
+}
+
+/// Converts a binary coded decimal `value` into binary.
+fn from_bcd(value: u8) -> u8 {
+    (value >> 4) * 10 + (value & 0x0f)
+}
+
+/// Converts `value` below 100 into binary coded decimal.
+fn to_bcd(value: u8) -> u8 {
+    ((value / 10) << 4) | (value % 10)
+}
+
+/// A DS3231 real-time clock attached to an I2C bus.
+#[derive(Debug)]
+pub struct Ds3231<B> {
+    bus: B,
+}
+
+impl<B: I2c> Ds3231<B> {
+    /// The fixed I2C address of the DS3231.
+    pub const ADDRESS: u8 = 0x68;
+
+    /// The register holding the seconds, followed by the other time and date registers.
+    const SECONDS: u8 = 0x00;
+    /// The status register.
+    const STATUS: u8 = 0x0f;
+    /// The register holding the integer part of the temperature, followed by its fraction.
+    const TEMPERATURE: u8 = 0x11;
+
+    /// The status flag telling that the oscillator stopped and the time is invalid.
+    const OSCILLATOR_STOPPED: u8 = 0x80;
+    /// The hour register flag for the 12 hour format.
+    const HOURS_12: u8 = 0x40;
+    /// The hour register flag for PM in the 12 hour format.
+    const HOURS_PM: u8 = 0x20;
+    /// The month register flag for years from 2100.
+    const CENTURY: u8 = 0x80;
+
+    /// Creates a new `Ds3231` on `bus`.
+    pub fn new(bus: B) -> Self {
+        Self { bus }
+    }
+
+    /// Returns the underlying I2C bus.
+    pub fn free(self) -> B {
+        self.bus
+    }
+
+    /// Reads the current date and time. Returns `None` if the clock lost its time, for example
+    /// because it was powered off without a backup battery. Set it again with [`Ds3231::set`].
+    pub async fn now(&mut self) -> Result<Option<DateTime>, B::Error> {
+        let mut status = [0];
+        self.bus
+            .write_read(Self::ADDRESS, &[Self::STATUS], &mut status)
+            .await?;
+        if status[0] & Self::OSCILLATOR_STOPPED != 0 {
+            log::debug!("DS3231 lost its time");
+            return Ok(None);
+        }
+
+        let mut registers = [0; 7];
+        self.bus
+            .write_read(Self::ADDRESS, &[Self::SECONDS], &mut registers)
+            .await?;
+        let [seconds, minutes, hours, _weekday, day, month, year] = registers;
+
+        let hour = if hours & Self::HOURS_12 != 0 {
+            let hour = from_bcd(hours & 0x1f) % 12;
+            match hours & Self::HOURS_PM {
+                0 => hour,
+                _ => hour + 12,
+            }
+        } else {
+            from_bcd(hours & 0x3f)
+        };
+        let century = match month & Self::CENTURY {
+            0 => 2000,
+            _ => 2100,
+        };
+
+        Ok(Some(DateTime {
+            year: century + u16::from(from_bcd(year)),
+            month: from_bcd(month & 0x1f),
+            day: from_bcd(day & 0x3f),
+            hour,
+            minute: from_bcd(minutes & 0x7f),
+            second: from_bcd(seconds & 0x7f),
+        }))
+    }
+
+    /// Sets the clock to `date_time` in the 24 hour format and marks the time as valid. Years
+    /// outside of 2000 to 2199 get clamped to this range.
+    pub async fn set(&mut self, date_time: DateTime) -> Result<(), B::Error> {
+        let year = date_time.year.clamp(2000, 2199) - 2000;
+        let century = match year {
+            0..100 => 0,
+            _ => Self::CENTURY,
+        };
+
+        self.bus
+            .write(
+                Self::ADDRESS,
+                &[
+                    Self::SECONDS,
+                    to_bcd(date_time.second),
+                    to_bcd(date_time.minute),
+                    to_bcd(date_time.hour),
+                    date_time.weekday(),
+                    to_bcd(date_time.day),
+                    to_bcd(date_time.month) | century,
+                    to_bcd((year % 100) as u8),
+                ],
+            )
+            .await?;
+
+        // Clear the oscillator stop flag and leave the 32 kHz output disabled.
+        self.bus.write(Self::ADDRESS, &[Self::STATUS, 0x00]).await
+    }
+
+    /// Reads the temperature in degrees Celsius from the sensor used for compensating the
+    /// oscillator. It gets updated every 64 seconds with a resolution of 0.25 °C.
+    pub async fn temperature(&mut self) -> Result<f32, B::Error> {
+        let mut registers = [0; 2];
+        self.bus
+            .write_read(Self::ADDRESS, &[Self::TEMPERATURE], &mut registers)
+            .await?;
+
+        // A signed fixed point number with two fractional bits at the top of the second byte.
+        let raw = i16::from_be_bytes(registers) >> 6;
+        Ok(f32::from(raw) / 4.0)
+    }
+}
+
+impl<B: I2c> Sensor for Ds3231<B> {
+    fn name(&self) -> &'static str {
+        "ds3231"
+    }
+
+    async fn sample(&mut self) -> Option<Value> {
+        match self.temperature().await {
+            Ok(celsius) => Some(Value::Temperature(celsius)),
+            Err(e) => {
+                log::debug!("DS3231: {:?}", e);
+                None
+            }
+        }
+    }
+}
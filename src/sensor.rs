@@ -0,0 +1,222 @@
+//! Periodic sampling of sensors at individual rates and publishing their readings.
+//!
+//! A [`Poller`] keeps the schedule for a set of sensors and [`Poller::next`] waits until the next
+//! one is due. Sensors implementing [`Sensor`] get sampled with [`poll`], which publishes the
+//! [`Reading`] to all tasks which [`subscribe`]d for them. For example for logging them or for
+//! forwarding them to some telemetry service.
+//!
+//! ```rust
+//! let mut chip = ChipTemperature::new(board.peripherals.tsens)?;
+//! let mut battery = BatteryVoltage::new(/* ... */);
+//!
+//! let mut poller = Poller::<2>::new();
+//! let chip_slot = poller.add(Period::from_secs(10)).unwrap();
+//! let battery_slot = poller.add(Period::from_secs(60)).unwrap();
+//!
+//! loop {
+//!     match poller.next().await {
+//!         slot if slot == chip_slot => sensor::poll(&mut chip).await,
+//!         slot if slot == battery_slot => sensor::poll(&mut battery).await,
+//!         _ => None,
+//!     };
+//! }
+//! ```
+
+use core::future::Future;
+
+use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
+use embassy_sync::pubsub::{PubSubChannel, Subscriber as PubSubSubscriber};
+use embassy_time::{Duration, Instant, Timer};
+use esp_hal::peripherals::TSENS;
+use esp_hal::tsens::{Config, ConfigError, TemperatureSensor};
+use heapless::Vec;
+
+use crate::config;
+use crate::led::Period;
+
+/// The maximum number of simultaneous subscribers for [`Reading`]s. See
+/// [`config::SENSOR_SUBSCRIBERS`] for changing it.
+pub const MAX_SUBSCRIBERS: usize = config::SENSOR_SUBSCRIBERS;
+
+/// The number of readings buffered for each subscriber. A subscriber lagging further behind misses
+/// the oldest ones. See [`config::SENSOR_CAPACITY`] for changing it.
+pub const CAPACITY: usize = config::SENSOR_CAPACITY;
+
+/// A value measured by a sensor.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum Value {
+    /// A temperature in degrees Celsius.
+    Temperature(f32),
+    /// A relative humidity in percent.
+    Humidity(f32),
+    /// A voltage in millivolts, for example of a battery.
+    Voltage(u32),
+    /// A distance in millimetres.
+    Distance(u32),
+}
+
+/// A value published by [`poll`] or [`publish`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Reading {
+    /// The name of the sensor, see [`Sensor::name`].
+    pub sensor: &'static str,
+    /// The value measured.
+    pub value: Value,
+    /// When the value got measured.
+    pub at: Instant,
+}
+
+/// A sensor which can be sampled by [`poll`].
+pub trait Sensor {
+    /// A short name telling apart the readings from different sensors.
+    fn name(&self) -> &'static str;
+
+    /// Measures a value. Returns `None` if the sensor failed to deliver one.
+    fn sample(&mut self) -> impl Future<Output = Option<Value>>;
+}
+
+/// A subscription for [`Reading`]s. Await its `next_message_pure()` method for waiting for one.
+pub type Subscriber =
+    PubSubSubscriber<'static, CriticalSectionRawMutex, Reading, CAPACITY, MAX_SUBSCRIBERS, 0>;
+
+static READINGS: PubSubChannel<CriticalSectionRawMutex, Reading, CAPACITY, MAX_SUBSCRIBERS, 0> =
+    PubSubChannel::new();
+
+/// Subscribes for [`Reading`]s. Returns `None` if there are already [`MAX_SUBSCRIBERS`].
+pub fn subscribe() -> Option<Subscriber> {
+    READINGS.subscriber().ok()
+}
+
+/// Publishes `reading` to all subscribers.
+pub fn publish(reading: Reading) {
+    log::trace!("{:?}", reading);
+    READINGS.immediate_publisher().publish_immediate(reading);
+}
+
+/// Samples `sensor` and publishes its reading. Returns the reading or `None` if sampling failed.
+pub async fn poll(sensor: &mut impl Sensor) -> Option<Reading> {
+    let Some(value) = sensor.sample().await else {
+        log::warn!("sampling {} failed", sensor.name());
+        return None;
+    };
+
+    let reading = Reading {
+        sensor: sensor.name(),
+        value,
+        at: Instant::now(),
+    };
+    publish(reading);
+    Some(reading)
+}
+
+/// Identifies a sensor added to a [`Poller`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Slot(usize);
+
+#[derive(Debug)]
+struct Schedule {
+    period: Period,
+    due: Instant,
+}
+
+/// Keeps the sampling schedule for up to `N` sensors.
+///
+/// Every sensor gets sampled at its own period. A sample which is due while another one is still
+/// being taken gets delayed. If it got delayed for more than its period, the missed samples are
+/// skipped instead of being caught up with.
+#[derive(Debug, Default)]
+pub struct Poller<const N: usize> {
+    schedules: Vec<Schedule, N>,
+}
+
+impl<const N: usize> Poller<N> {
+    /// Creates a new poller without any sensors.
+    pub const fn new() -> Self {
+        Self {
+            schedules: Vec::new(),
+        }
+    }
+
+    /// Adds a sensor to sample every `period`, starting right away. Returns `None` if there are
+    /// already `N` sensors.
+    pub fn add(&mut self, period: impl Into<Period>) -> Option<Slot> {
+        let slot = Slot(self.schedules.len());
+        let schedule = Schedule {
+            period: period.into(),
+            due: Instant::now(),
+        };
+
+        self.schedules.push(schedule).ok()?;
+        Some(slot)
+    }
+
+    /// Waits until the next sensor is due for sampling and returns its slot. This waits forever if
+    /// no sensor has been added.
+    pub async fn next(&mut self) -> Slot {
+        let Some((index, schedule)) = self
+            .schedules
+            .iter_mut()
+            .enumerate()
+            .min_by_key(|(_, schedule)| schedule.due)
+        else {
+            return core::future::pending().await;
+        };
+
+        Timer::at(schedule.due).await;
+
+        let period = schedule.period.as_duration().as_ticks();
+        let late = Instant::now().saturating_duration_since(schedule.due);
+        let missed = late.as_ticks() / period;
+        if missed > 0 {
+            log::trace!("skipped {} sample(s)", missed);
+        }
+        let next = missed.saturating_add(1).saturating_mul(period);
+        schedule.due = Instant::from_ticks(schedule.due.as_ticks().saturating_add(next));
+
+        Slot(index)
+    }
+}
+
+/// The temperature sensor built into the ESP32-C3.
+///
+/// It measures the temperature of the chip, which is generally a bit above the temperature of its
+/// surroundings.
+#[derive(Debug)]
+pub struct ChipTemperature<'a> {
+    sensor: TemperatureSensor<'a>,
+    ready: Instant,
+}
+
+impl<'a> ChipTemperature<'a> {
+    /// The time the sensor needs for stabilizing after powering it up.
+    const SETTLING: Duration = Duration::from_micros(300);
+
+    /// Creates a new `ChipTemperature` and powers up the sensor.
+    pub fn new(tsens: TSENS<'a>) -> Result<Self, ConfigError> {
+        let sensor = TemperatureSensor::new(tsens, Config::default())?;
+
+        Ok(Self {
+            sensor,
+            ready: Instant::now() + Self::SETTLING,
+        })
+    }
+
+    /// Returns the underlying esp-hal sensor.
+    pub fn free(self) -> TemperatureSensor<'a> {
+        self.sensor
+    }
+}
+
+impl Sensor for ChipTemperature<'_> {
+    fn name(&self) -> &'static str {
+        "chip"
+    }
+
+    async fn sample(&mut self) -> Option<Value> {
+        Timer::at(self.ready).await;
+        Some(Value::Temperature(
+            self.sensor.get_temperature().to_celsius(),
+        ))
+    }
+}
@@ -0,0 +1,144 @@
+//! Async access to the shake sensor _U2_, from single shakes to shaking gestures.
+//!
+//! The shake sensor is a tiny spring switch which closes when it gets accelerated along its axis
+//! (you can hear its click sound). It bounces several times whenever it activates. So the
+//! [`ShakeSensor`] considers the first falling edge as the shake and ignores everything else for a
+//! debounce period.
+//!
+//! ```rust
+//! let mut shake = board.u2;
+//!
+//! // Wait for the board getting shaken back and forth three times within two seconds.
+//! shake.wait_for_n_shakes::<3>(Duration::from_secs(2)).await;
+//! ```
+
+use embassy_time::{Duration, Instant, Timer};
+use esp_hal::gpio::Input;
+use heapless::Deque;
+
+/// A single shake reported from [`Shakes::next`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Shake {
+    /// When the shake sensor activated.
+    pub at: Instant,
+    /// The time since the previous shake. This is `None` for the first one.
+    pub since_previous: Option<Duration>,
+}
+
+/// The shake sensor _U2_.
+#[derive(Debug)]
+pub struct ShakeSensor<'a> {
+    input: Input<'a>,
+    debounce: Duration,
+}
+
+impl<'a> ShakeSensor<'a> {
+    /// The default time for ignoring the sensor bouncing after a shake.
+    pub const DEFAULT_DEBOUNCE: Duration = Duration::from_millis(150);
+
+    /// Creates a new `ShakeSensor` from the input it is connected to. The input needs a pull-up.
+    pub fn new(input: Input<'a>) -> Self {
+        Self {
+            input,
+            debounce: Self::DEFAULT_DEBOUNCE,
+        }
+    }
+
+    /// Sets the time for ignoring further activations after a shake.
+    ///
+    /// A longer debounce time makes the sensor less sensitive: Rapid shakes will be counted as a
+    /// single one. A shorter time risks counting the bouncing of a single shake multiple times.
+    pub fn with_debounce(self, debounce: Duration) -> Self {
+        Self { debounce, ..self }
+    }
+
+    /// Returns the input the sensor is connected to for using esp-hal functionality not covered
+    /// here.
+    pub fn input_mut(&mut self) -> &mut Input<'a> {
+        &mut self.input
+    }
+
+    /// Returns the input the sensor is connected to.
+    pub fn free(self) -> Input<'a> {
+        self.input
+    }
+
+    /// Waits for a single shake and returns when it happened.
+    ///
+    /// This includes waiting for the debounce period after the shake. So back to back calls won't
+    /// report the sensor bouncing as separate shakes.
+    pub async fn wait_for_shake(&mut self) -> Instant {
+        self.input.wait_for_falling_edge().await;
+        let at = Instant::now();
+        log::debug!("shake");
+
+        Timer::after(self.debounce).await;
+        at
+    }
+
+    /// Waits for `N` shakes with no more than `within` between the first and the last one.
+    ///
+    /// The latest `N` shakes are kept, so any `N` consecutive shakes within the time window count.
+    /// A slow shake at the beginning does not prevent quicker ones from completing the gesture.
+    pub async fn wait_for_n_shakes<const N: usize>(&mut self, within: Duration) {
+        if N == 0 {
+            return;
+        }
+
+        let mut latest: Deque<Instant, N> = Deque::new();
+
+        loop {
+            let at = self.wait_for_shake().await;
+
+            if latest.is_full() {
+                latest.pop_front();
+            }
+            // There is room after dropping the oldest shake.
+            let _ = latest.push_back(at);
+
+            if let (true, Some(oldest)) = (latest.is_full(), latest.front()) {
+                if at.duration_since(*oldest) <= within {
+                    return;
+                }
+            }
+        }
+    }
+
+    /// Returns a continuous stream of shakes. See [`Shakes`].
+    pub fn events(&mut self) -> Shakes<'_, 'a> {
+        Shakes {
+            sensor: self,
+            previous: None,
+        }
+    }
+}
+
+/// A continuous stream of shakes, for example for consuming them in a task and signalling them to
+/// others.
+///
+/// ```rust
+/// #[embassy_executor::task]
+/// async fn shake_task(mut sensor: ShakeSensor<'static>, signal: &'static ShakeSignal) {
+///     let mut shakes = sensor.events();
+///
+///     loop {
+///         signal.signal(shakes.next().await);
+///     }
+/// }
+/// ```
+#[derive(Debug)]
+pub struct Shakes<'s, 'a> {
+    sensor: &'s mut ShakeSensor<'a>,
+    previous: Option<Instant>,
+}
+
+impl Shakes<'_, '_> {
+    /// Waits for the next shake.
+    pub async fn next(&mut self) -> Shake {
+        let at = self.sensor.wait_for_shake().await;
+        let since_previous = self.previous.map(|previous| at.duration_since(previous));
+        self.previous = Some(at);
+
+        Shake { at, since_previous }
+    }
+}
@@ -1,6 +1,16 @@
 //! GPIO pin abstractions for switching things on an off whithout having to remember the actual
 //! hardware setup behing it.
 
+// Outputs get switched from within animation loops where a panic would silently stop the show.
+#![deny(
+    clippy::expect_used,
+    clippy::indexing_slicing,
+    clippy::panic,
+    clippy::unwrap_used
+)]
+
+use core::convert::Infallible;
+use embedded_hal::digital::{ErrorType, OutputPin, StatefulOutputPin};
 use esp_hal::gpio::Output;
 
 /// Convenience wrapper for switching outputs (like LEDs) without having to remember the actual
@@ -19,6 +29,16 @@ impl<'a> LowActiveSwitch<'a> {
         Self { inner: output }
     }
 
+    /// Returns the underlying output for using esp-hal functionality not covered here.
+    pub fn output_mut(&mut self) -> &mut Output<'a> {
+        &mut self.inner
+    }
+
+    /// Returns the underlying output.
+    pub fn free(self) -> Output<'a> {
+        self.inner
+    }
+
     /// Turns the output on.
     pub fn switch_off(&mut self) {
         self.inner.set_high();
@@ -37,3 +57,33 @@ impl<'a> LowActiveSwitch<'a> {
         }
     }
 }
+
+impl ErrorType for LowActiveSwitch<'_> {
+    type Error = Infallible;
+}
+
+/// Forwards the electrical pin level to the underlying output for use with generic drivers.
+///
+/// Keep in mind that this is the pin level and not the switch state: setting the pin low switches
+/// the output on and setting it high switches it off.
+impl OutputPin for LowActiveSwitch<'_> {
+    fn set_low(&mut self) -> Result<(), Self::Error> {
+        self.inner.set_low();
+        Ok(())
+    }
+
+    fn set_high(&mut self) -> Result<(), Self::Error> {
+        self.inner.set_high();
+        Ok(())
+    }
+}
+
+impl StatefulOutputPin for LowActiveSwitch<'_> {
+    fn is_set_high(&mut self) -> Result<bool, Self::Error> {
+        Ok(self.inner.is_set_high())
+    }
+
+    fn is_set_low(&mut self) -> Result<bool, Self::Error> {
+        Ok(self.inner.is_set_low())
+    }
+}
@@ -0,0 +1,94 @@
+//! Rebooting and shutting down the board in an orderly fashion.
+//!
+//! Instead of just pulling the plug, [`reboot`] and [`shutdown`] first broadcast an [`Event`] to
+//! all tasks which [`subscribe`]d for it and give them [`GRACE_PERIOD`] to wrap up their work.
+//! For example switching off LEDs or finishing a write which should not be interrupted.
+//!
+//! ```rust
+//! let mut shutdown = system::subscribe().unwrap();
+//!
+//! match select(storeys.cycle(step), shutdown.changed()).await {
+//!     Either::First(_) => {}
+//!     Either::Second(_) => storeys.all_off(),
+//! }
+//! ```
+
+use core::cell::RefCell;
+
+use critical_section::Mutex;
+use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
+use embassy_sync::watch::{Receiver, Watch};
+use embassy_time::{Duration, Timer};
+use esp_hal::peripherals::LPWR;
+use esp_hal::rtc_cntl::Rtc;
+
+use crate::config;
+
+/// The maximum number of simultaneous subscribers for [`Event`]s. See
+/// [`config::SYSTEM_SUBSCRIBERS`] for changing it.
+pub const MAX_SUBSCRIBERS: usize = config::SYSTEM_SUBSCRIBERS;
+
+/// The time subscribers get for wrapping up their work after an [`Event`] got broadcast.
+pub const GRACE_PERIOD: Duration = Duration::from_millis(100);
+
+/// Why the board is going to be rebooted.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Reason {
+    /// The application requested a reboot, for example on user request.
+    Requested,
+    /// The application ran into an error it can't recover from otherwise.
+    Error,
+}
+
+/// The event broadcast to subscribers before the board goes down.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Event {
+    /// The board is going to be rebooted.
+    Reboot(Reason),
+    /// The board is going to be shut down.
+    Shutdown,
+}
+
+/// A subscription for [`Event`]s. Await its `changed()` method for waiting for one.
+pub type Subscriber = Receiver<'static, CriticalSectionRawMutex, Event, MAX_SUBSCRIBERS>;
+
+static EVENTS: Watch<CriticalSectionRawMutex, Event, MAX_SUBSCRIBERS> = Watch::new();
+static RTC: Mutex<RefCell<Option<Rtc<'static>>>> = Mutex::new(RefCell::new(None));
+
+/// Takes the RTC peripheral for entering deep sleep on [`shutdown`].
+pub(crate) fn init(lpwr: LPWR<'static>) {
+    critical_section::with(|cs| RTC.replace(cs, Some(Rtc::new(lpwr))));
+}
+
+/// Subscribes for [`Event`]s. Returns `None` if there are already [`MAX_SUBSCRIBERS`].
+pub fn subscribe() -> Option<Subscriber> {
+    EVENTS.receiver()
+}
+
+/// Broadcasts `event` and waits for the subscribers to wrap up.
+async fn broadcast(event: Event) {
+    log::info!("{:?}", event);
+    EVENTS.sender().send(event);
+    Timer::after(GRACE_PERIOD).await;
+}
+
+/// Reboots the board after giving subscribers the chance to wrap up.
+pub async fn reboot(reason: Reason) -> ! {
+    broadcast(Event::Reboot(reason)).await;
+    esp_hal::system::software_reset()
+}
+
+/// Shuts the board down after giving subscribers the chance to wrap up.
+///
+/// The board is put into deep sleep without any wakeup source. Press the reset button on the
+/// ESP32-C3 board _U1_ or cycle power for starting again.
+///
+/// # Panics
+///
+/// Shutting down requires the board being initialized with [`crate::board::Board::init`] before.
+pub async fn shutdown() -> ! {
+    broadcast(Event::Shutdown).await;
+
+    let rtc = critical_section::with(|cs| RTC.take(cs));
+    rtc.expect("board not initialized").sleep_deep(&[])
+}
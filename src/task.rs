@@ -0,0 +1,32 @@
+//! Helpers for spawning tasks without bricking the board on failure.
+//!
+//! Spawning a task fails if all instances from its pool are already running. Unwrapping the
+//! result panics and leaves the board silently stuck. [`spawn_or_report`] logs the failure instead
+//! and lets the caller decide how to carry on without the task.
+//!
+//! ```rust
+//! if !task::spawn_or_report(&spawner, "button", button_task(board.sw1)) {
+//!     // Carry on without button input.
+//! }
+//! ```
+
+use embassy_executor::{SpawnToken, Spawner};
+
+/// Spawns the task behind `token` on `spawner`. Returns whether it got spawned. A failure gets
+/// logged as error mentioning `name`.
+pub fn spawn_or_report<S>(spawner: &Spawner, name: &str, token: SpawnToken<S>) -> bool {
+    match spawner.spawn(token) {
+        Ok(()) => {
+            log::debug!("spawned task {}", name);
+            true
+        }
+        Err(e) => {
+            log::error!(
+                "failed to spawn task {}: {:?}, increase its pool_size for running more instances",
+                name,
+                e
+            );
+            false
+        }
+    }
+}
@@ -0,0 +1,133 @@
+//! A poor man's logic analyzer for debugging switch bounce and sensor signals.
+//!
+//! [`Capture::record`] samples a set of inputs at a fixed rate and keeps track of their
+//! transitions in RAM. [`Capture::dump_vcd`] prints them in the
+//! [Value Change Dump](https://en.wikipedia.org/wiki/Value_change_dump) format which can be viewed
+//! with [GTKWave](https://gtkwave.sourceforge.net/) or [Surfer](https://surfer-project.org/).
+//!
+//! ```rust
+//! let capture: Capture<256> =
+//!     Capture::record(&[&board.sw1], Duration::from_micros(100), Duration::from_secs(5)).await;
+//! capture.dump_vcd(&["sw1"]);
+//! ```
+
+use embassy_time::{Duration, Instant, Ticker};
+use esp_hal::gpio::Input;
+use esp_println::println;
+use heapless::Vec;
+
+/// The maximum number of inputs which can be sampled at once.
+pub const MAX_CHANNELS: usize = 32;
+
+/// The levels of all sampled inputs at a certain point in time.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Transition {
+    /// When the levels changed.
+    pub at: Instant,
+    /// The input levels. Bit _n_ is set if input _n_ was high.
+    pub levels: u32,
+}
+
+/// The transitions recorded from a set of inputs. Keeps up to `N` transitions including the
+/// initial levels.
+#[derive(Debug)]
+pub struct Capture<const N: usize> {
+    start: Instant,
+    channels: usize,
+    transitions: Vec<Transition, N>,
+}
+
+impl<const N: usize> Capture<N> {
+    /// Samples `inputs` every `period` for `duration` and records their transitions.
+    ///
+    /// Recording stops early when there is no more room for transitions. Only the first
+    /// [`MAX_CHANNELS`] inputs are sampled.
+    pub async fn record(inputs: &[&Input<'_>], period: Duration, duration: Duration) -> Self {
+        let channels = inputs.len().min(MAX_CHANNELS);
+        let start = Instant::now();
+        let end = start + duration;
+        let mut ticker = Ticker::every(period);
+        let mut transitions = Vec::new();
+        let mut previous = None;
+
+        log::info!(
+            "recording {} input(s) for {} ms",
+            channels,
+            duration.as_millis()
+        );
+
+        loop {
+            let at = Instant::now();
+            if at >= end {
+                break;
+            }
+
+            let levels = inputs
+                .iter()
+                .take(channels)
+                .enumerate()
+                .filter(|(_, input)| input.is_high())
+                .fold(0, |levels, (index, _)| levels | 1 << index);
+
+            if previous != Some(levels) {
+                if transitions.push(Transition { at, levels }).is_err() {
+                    log::warn!("trace buffer full, stopping early");
+                    break;
+                }
+                previous = Some(levels);
+            }
+
+            ticker.next().await;
+        }
+
+        log::info!("recorded {} transition(s)", transitions.len());
+
+        Self {
+            start,
+            channels,
+            transitions,
+        }
+    }
+
+    /// The recorded transitions. The first one holds the initial input levels.
+    pub fn transitions(&self) -> &[Transition] {
+        &self.transitions
+    }
+
+    /// Prints the recorded transitions in the Value Change Dump format. The inputs are named
+    /// after `names` in the order they were passed to [`Capture::record`] or numbered if there are
+    /// not enough names.
+    pub fn dump_vcd(&self, names: &[&str]) {
+        println!("$timescale 1 us $end");
+        println!("$scope module hakkaa $end");
+        for channel in 0..self.channels {
+            match names.get(channel) {
+                Some(name) => println!("$var wire 1 {} {} $end", id(channel), name),
+                None => println!("$var wire 1 {} input{} $end", id(channel), channel),
+            }
+        }
+        println!("$upscope $end");
+        println!("$enddefinitions $end");
+
+        let mut previous: Option<u32> = None;
+        for transition in self.transitions.iter() {
+            println!("#{}", transition.at.duration_since(self.start).as_micros());
+
+            for channel in 0..self.channels {
+                let level = transition.levels >> channel & 1;
+                let changed = previous.is_none_or(|previous| previous >> channel & 1 != level);
+
+                if changed {
+                    println!("{}{}", level, id(channel));
+                }
+            }
+
+            previous = Some(transition.levels);
+        }
+    }
+}
+
+/// The VCD identifier for `channel`. These are single printable ASCII characters starting at `!`.
+fn id(channel: usize) -> char {
+    char::from(b'!' + channel as u8)
+}
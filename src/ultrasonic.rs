@@ -0,0 +1,180 @@
+//! Support for HC-SR04 style ultrasonic distance sensors connected to spare pins.
+//!
+//! The sensor gets triggered by a short pulse and answers with an echo pulse as long as the sound
+//! took for travelling to the obstacle and back. Mind that the HC-SR04 runs at 5 V. Its echo
+//! output needs a voltage divider before being connected to an input of the ESP32-C3.
+//!
+//! ```rust
+//! let trigger = Output::new(board.peripherals.gpio9, Level::Low, OutputConfig::default());
+//! let echo = Input::new(board.peripherals.gpio2, InputConfig::default());
+//! let mut sensor = DistanceSensor::new(trigger, echo).with_threshold(50);
+//!
+//! loop {
+//!     match sensor.next().await {
+//!         Event::Moved(distance) => log::info!("{} mm", distance),
+//!         Event::Lost => log::info!("out of range"),
+//!     }
+//! }
+//! ```
+//!
+//! The echo pulse gets timed in software from when the waiting task resumes after each of its
+//! edges. Other tasks and interrupts delaying this add to the measured time. Every microsecond of
+//! delay adds about 0.17 mm, so a task blocking the executor for a millisecond during an echo
+//! makes the obstacle appear 17 cm farther away. Readings are thus only as precise as the
+//! executor is responsive and [`DistanceSensor::with_threshold`] should leave some margin for
+//! this jitter.
+//!
+//! A [`DistanceSensor`] is a [`Sensor`] and can be sampled by a [`crate::sensor::Poller`] as
+//! well.
+
+use core::future::Future;
+use embassy_futures::select::{select, Either};
+use embassy_time::{Duration, Instant, Timer};
+use esp_hal::gpio::{Input, Output};
+
+use crate::sensor::{Sensor, Value};
+
+/// A change of the distance reported from [`DistanceSensor::next`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Event {
+    /// An obstacle came into range or moved by at least the threshold. Carries its distance in
+    /// millimeters.
+    Moved(u32),
+    /// The obstacle went out of range. There was no echo anymore.
+    Lost,
+}
+
+/// An ultrasonic distance sensor with separate trigger and echo pins like the HC-SR04.
+#[derive(Debug)]
+pub struct DistanceSensor<'a> {
+    trigger: Output<'a>,
+    echo: Input<'a>,
+    threshold: u32,
+    interval: Duration,
+    /// The distance last reported by [`DistanceSensor::next`].
+    reported: Option<u32>,
+    /// When the sensor has settled for the next measurement.
+    ready: Instant,
+}
+
+impl<'a> DistanceSensor<'a> {
+    /// How long to wait for the echo. This covers HC-SR04's maximum range of about 4 m with some
+    /// margin.
+    pub const TIMEOUT: Duration = Duration::from_millis(30);
+    /// The time the sensor needs for settling between two measurements as recommended by the
+    /// HC-SR04 datasheet.
+    pub const SETTLING: Duration = Duration::from_millis(60);
+    /// The default change in millimeters reported by [`DistanceSensor::next`].
+    pub const DEFAULT_THRESHOLD: u32 = 20;
+    /// The default time between two measurements of [`DistanceSensor::next`].
+    pub const DEFAULT_INTERVAL: Duration = Duration::from_millis(100);
+
+    /// Creates a new `DistanceSensor` from the output connected to the trigger pin (initially
+    /// low) and the input connected to the echo pin.
+    pub fn new(trigger: Output<'a>, echo: Input<'a>) -> Self {
+        Self {
+            trigger,
+            echo,
+            threshold: Self::DEFAULT_THRESHOLD,
+            interval: Self::DEFAULT_INTERVAL,
+            reported: None,
+            ready: Instant::now(),
+        }
+    }
+
+    /// Sets the change of the distance in millimeters to be reported by
+    /// [`DistanceSensor::next`].
+    pub fn with_threshold(self, threshold: u32) -> Self {
+        Self { threshold, ..self }
+    }
+
+    /// Sets the time between two measurements of [`DistanceSensor::next`]. Intervals shorter
+    /// than [`DistanceSensor::SETTLING`] get extended to it.
+    pub fn with_interval(self, interval: Duration) -> Self {
+        Self { interval, ..self }
+    }
+
+    /// Returns the trigger output and the echo input.
+    pub fn free(self) -> (Output<'a>, Input<'a>) {
+        (self.trigger, self.echo)
+    }
+
+    /// Measures the distance to the next obstacle in millimeters. Returns `None` if there was no
+    /// echo within [`DistanceSensor::TIMEOUT`].
+    ///
+    /// This waits for [`DistanceSensor::SETTLING`] after the previous measurement. See the
+    /// [module documentation](self) for the precision to expect.
+    pub async fn measure(&mut self) -> Option<u32> {
+        Timer::at(self.ready).await;
+
+        self.trigger.set_high();
+        Timer::after_micros(10).await;
+        self.trigger.set_low();
+
+        let deadline = Instant::now() + Self::TIMEOUT;
+        let echo = Self::until(deadline, self.echo.wait_for_high()).await;
+        let echo = match echo {
+            Some(start) => Self::until(deadline, self.echo.wait_for_low())
+                .await
+                .map(|end| end.duration_since(start)),
+            None => None,
+        };
+        self.ready = Instant::now() + Self::SETTLING;
+
+        // Sound travels at about 343 m/s, which is 0.343 mm per microsecond. The echo took the
+        // distance twice.
+        let micros = echo?.as_micros();
+        let distance = micros * 343 / 2000;
+
+        log::trace!("echo after {} us: {} mm", micros, distance);
+        distance.try_into().ok()
+    }
+
+    /// Waits for the next change of the distance by at least the threshold or for the obstacle
+    /// coming into or going out of range. Measures every interval until then.
+    pub async fn next(&mut self) -> Event {
+        loop {
+            let start = Instant::now();
+            let distance = self.measure().await;
+
+            let event = match (self.reported, distance) {
+                (None, Some(distance)) => Some(Event::Moved(distance)),
+                (Some(reported), Some(distance))
+                    if reported.abs_diff(distance) >= self.threshold =>
+                {
+                    Some(Event::Moved(distance))
+                }
+                (Some(_), None) => Some(Event::Lost),
+                _ => None,
+            };
+            if let Some(event) = event {
+                self.reported = distance;
+                log::debug!("distance: {:?}", event);
+                return event;
+            }
+
+            Timer::at(start + self.interval).await;
+        }
+    }
+
+    /// Waits for `edge` until `deadline` and returns when it occurred.
+    async fn until(deadline: Instant, edge: impl Future<Output = ()>) -> Option<Instant> {
+        match select(edge, Timer::at(deadline)).await {
+            Either::First(_) => Some(Instant::now()),
+            Either::Second(_) => {
+                log::debug!("no echo");
+                None
+            }
+        }
+    }
+}
+
+impl Sensor for DistanceSensor<'_> {
+    fn name(&self) -> &'static str {
+        "hc-sr04"
+    }
+
+    async fn sample(&mut self) -> Option<Value> {
+        self.measure().await.map(Value::Distance)
+    }
+}
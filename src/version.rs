@@ -0,0 +1,71 @@
+//! Firmware metadata like the crate version, the Git revision it got built from, and the enabled
+//! features and dependencies.
+
+use core::fmt;
+
+use crate::config::parse_u64;
+
+/// Information about the firmware build.
+#[derive(Debug)]
+pub struct Version {
+    /// The semantic version of this crate.
+    pub semver: &'static str,
+    /// The abbreviated Git commit hash the firmware got built from or `unknown` if this
+    /// information was not available at build time.
+    pub git_hash: &'static str,
+    /// The build time in seconds since the Unix epoch.
+    pub build_timestamp: u64,
+    features: &'static str,
+    dependencies: &'static str,
+}
+
+impl Version {
+    /// Returns an iterator over the names of the cargo features enabled for this crate.
+    pub fn features(&self) -> impl Iterator<Item = &'static str> {
+        self.features
+            .split(',')
+            .filter(|feature| !feature.is_empty())
+    }
+
+    /// Returns an iterator over the names and versions of the most relevant dependencies like
+    /// esp-hal and embassy. Dependencies used in multiple versions show up once per version.
+    ///
+    /// The versions come from the `Cargo.lock` found above the build's target directory or this
+    /// crate's manifest. The iterator is empty if the build script found no lock file, for
+    /// example with a target directory outside of the workspace and no lock file shipped along.
+    pub fn dependencies(&self) -> impl Iterator<Item = (&'static str, &'static str)> {
+        self.dependencies
+            .split(',')
+            .filter_map(|dependency| dependency.split_once(' '))
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} ({}, built at {})",
+            self.semver, self.git_hash, self.build_timestamp
+        )?;
+
+        for (index, feature) in self.features().enumerate() {
+            let separator = if index == 0 { ", features: " } else { " " };
+            write!(f, "{separator}{feature}")?;
+        }
+
+        Ok(())
+    }
+}
+
+static VERSION: Version = Version {
+    semver: env!("CARGO_PKG_VERSION"),
+    git_hash: env!("HAKKAA_GIT_HASH"),
+    build_timestamp: parse_u64(env!("HAKKAA_BUILD_TIMESTAMP")),
+    features: env!("HAKKAA_FEATURES"),
+    dependencies: env!("HAKKAA_DEPENDENCIES"),
+};
+
+/// Returns the metadata of the firmware build.
+pub fn version() -> &'static Version {
+    &VERSION
+}